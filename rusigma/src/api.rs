@@ -0,0 +1,148 @@
+use crate::cbu::CbuOrAlias;
+use crate::client::SigmaClient;
+use crate::document::{Dni, Gender};
+use crate::errors::Error;
+use crate::filters::NameSearchFilters;
+use crate::phone::PhoneNumber;
+use crate::plate::Plate;
+use crate::response;
+use async_trait::async_trait;
+
+/// Every operation of the Sigma API
+///
+/// Code that depends on this trait instead of `SigmaClient` can be tested
+/// with `testing::FakeSigmaClient` (behind the `testing` feature).
+#[async_trait]
+pub trait SigmaApi: Send + Sync {
+    async fn login_with_credentials(&self, username: String, password: String)
+        -> Result<(), Error>;
+
+    async fn search_standard_dni(&self, dni: Dni) -> Result<response::DNIStandardResponse, Error>;
+
+    async fn search_phones_by_dni(&self, dni: Dni) -> Result<Vec<response::PhoneRecord>, Error>;
+
+    async fn search_plate(&self, plate: Plate) -> Result<Vec<response::PlateHistory>, Error>;
+
+    async fn search_plate_by_dni(&self, dni: Dni) -> Result<Vec<response::PlateHistory>, Error>;
+
+    async fn search_leaks(&self, query: String) -> Result<Vec<response::BreachCredentials>, Error>;
+
+    async fn search_profesional_dni(
+        &self,
+        dni: Dni,
+        gender: Gender,
+    ) -> Result<response::DNIProfesional, Error>;
+
+    async fn search_name(
+        &self,
+        name: String,
+        filters: Option<NameSearchFilters>,
+    ) -> Result<Vec<response::PersonaNombre>, Error>;
+
+    async fn search_movistar_email(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<response::MovistarEmail, Error>;
+
+    async fn search_by_address(
+        &self,
+        address: String,
+    ) -> Result<Vec<response::PersonaDireccion>, Error>;
+
+    async fn search_phone(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<Vec<response::PersonaFromNumero>, Error>;
+
+    async fn search_phone_magic(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<response::PersonaFromNumeroMagic, Error>;
+
+    async fn search_cbu(&self, cbu_or_alias: CbuOrAlias) -> Result<response::TitularCBU, Error>;
+
+    async fn search_email(&self, email: String) -> Result<response::EmailResultados, Error>;
+}
+
+#[async_trait]
+impl SigmaApi for SigmaClient {
+    async fn login_with_credentials(
+        &self,
+        username: String,
+        password: String,
+    ) -> Result<(), Error> {
+        SigmaClient::login_with_credentials(self, username, password).await
+    }
+
+    async fn search_standard_dni(&self, dni: Dni) -> Result<response::DNIStandardResponse, Error> {
+        SigmaClient::search_standard_dni(self, dni).await
+    }
+
+    async fn search_phones_by_dni(&self, dni: Dni) -> Result<Vec<response::PhoneRecord>, Error> {
+        SigmaClient::search_phones_by_dni(self, dni).await
+    }
+
+    async fn search_plate(&self, plate: Plate) -> Result<Vec<response::PlateHistory>, Error> {
+        SigmaClient::search_plate(self, plate).await
+    }
+
+    async fn search_plate_by_dni(&self, dni: Dni) -> Result<Vec<response::PlateHistory>, Error> {
+        SigmaClient::search_plate_by_dni(self, dni).await
+    }
+
+    async fn search_leaks(&self, query: String) -> Result<Vec<response::BreachCredentials>, Error> {
+        SigmaClient::search_leaks(self, query).await
+    }
+
+    async fn search_profesional_dni(
+        &self,
+        dni: Dni,
+        gender: Gender,
+    ) -> Result<response::DNIProfesional, Error> {
+        SigmaClient::search_profesional_dni(self, dni, gender).await
+    }
+
+    async fn search_name(
+        &self,
+        name: String,
+        filters: Option<NameSearchFilters>,
+    ) -> Result<Vec<response::PersonaNombre>, Error> {
+        SigmaClient::search_name(self, name, filters).await
+    }
+
+    async fn search_movistar_email(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<response::MovistarEmail, Error> {
+        SigmaClient::search_movistar_email(self, number).await
+    }
+
+    async fn search_by_address(
+        &self,
+        address: String,
+    ) -> Result<Vec<response::PersonaDireccion>, Error> {
+        SigmaClient::search_by_address(self, address).await
+    }
+
+    async fn search_phone(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<Vec<response::PersonaFromNumero>, Error> {
+        SigmaClient::search_phone(self, number).await
+    }
+
+    async fn search_phone_magic(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<response::PersonaFromNumeroMagic, Error> {
+        SigmaClient::search_phone_magic(self, number).await
+    }
+
+    async fn search_cbu(&self, cbu_or_alias: CbuOrAlias) -> Result<response::TitularCBU, Error> {
+        SigmaClient::search_cbu(self, cbu_or_alias).await
+    }
+
+    async fn search_email(&self, email: String) -> Result<response::EmailResultados, Error> {
+        SigmaClient::search_email(self, email).await
+    }
+}
@@ -0,0 +1,59 @@
+//! Synchronous `SigmaClient`, enabled by the `blocking` feature
+//!
+//! Its queries are generated with the async ones in `client`, and each runs
+//! the matching async method to completion on a private single-threaded
+//! runtime, so both clients send the same requests and return the same types. It must not be used from inside an
+//! async runtime: calling or dropping it there panics.
+
+use crate::builder::SigmaClientBuilder;
+use crate::client;
+use crate::errors::Error;
+use crate::http::HTTPClient;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Blocking Sigma API client
+///
+/// Clones share the runtime, the session and the connection pool.
+#[derive(Clone)]
+pub struct SigmaClient {
+    pub(crate) inner: client::SigmaClient,
+    pub(crate) runtime: Arc<Runtime>,
+}
+
+impl SigmaClient {
+    pub fn new() -> Result<SigmaClient, Error> {
+        SigmaClient::from_async(client::SigmaClient::new())
+    }
+
+    /// Returns the builder of the async client, finish it with `build_blocking`
+    pub fn builder() -> SigmaClientBuilder {
+        SigmaClientBuilder::new()
+    }
+
+    /// Creates a client from a `token:plan` string, see `client::SigmaClient::from_creds`
+    pub fn from_creds(credentials: String) -> Result<SigmaClient, Error> {
+        SigmaClient::from_async(client::SigmaClient::from_creds(credentials)?)
+    }
+
+    /// Wraps an async client, sharing its session and settings
+    pub fn from_async(inner: client::SigmaClient) -> Result<SigmaClient, Error> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| Error::InvalidConfiguration(format!("could not start runtime: {}", e)))?;
+        Ok(SigmaClient {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Low level client, to read or set the session
+    pub fn http(&self) -> &HTTPClient {
+        &self.inner.http
+    }
+
+    pub fn http_mut(&mut self) -> &mut HTTPClient {
+        &mut self.inner.http
+    }
+}
@@ -0,0 +1,296 @@
+#[cfg(feature = "cache")]
+use crate::cache::ResponseCache;
+use crate::client::SigmaClient;
+use crate::credentials::CredentialsProvider;
+use crate::decode::{DecodeMode, Warning, WarningHook};
+use crate::errors::Error;
+use crate::http::{HTTPClient, Session, TokenHook, DEFAULT_API_PATH, DEFAULT_BASE_URL};
+use crate::limits::{QueryBudget, RateLimiter};
+use crate::retry::RetryPolicy;
+use crate::transport::{ReqwestTransport, Transport};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+enum RootCertificate {
+    Pem(Vec<u8>),
+    Der(Vec<u8>),
+}
+
+/// Configures and builds a `SigmaClient`
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// let client = rusigma::SigmaClient::builder()
+///     .base_url("https://staging.sigma-search.io")
+///     .timeout(Duration::from_secs(30))
+///     .build()
+///     .unwrap();
+/// ```
+pub struct SigmaClientBuilder {
+    base_url: String,
+    api_path: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    root_certificates: Vec<RootCertificate>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    query_budget: Option<QueryBudget>,
+    transport: Option<Arc<dyn Transport>>,
+    decode_mode: DecodeMode,
+    on_warning: Option<WarningHook>,
+    credentials_provider: Option<Arc<dyn CredentialsProvider>>,
+    on_token_refreshed: Option<TokenHook>,
+    log_sensitive_data: bool,
+    #[cfg(feature = "cache")]
+    cache: Option<ResponseCache>,
+}
+
+impl Default for SigmaClientBuilder {
+    fn default() -> Self {
+        SigmaClientBuilder::new()
+    }
+}
+
+impl SigmaClientBuilder {
+    pub fn new() -> SigmaClientBuilder {
+        SigmaClientBuilder {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_path: DEFAULT_API_PATH.to_string(),
+            timeout: None,
+            connect_timeout: None,
+            user_agent: None,
+            proxy: None,
+            root_certificates: Vec::new(),
+            retry_policy: None,
+            rate_limiter: None,
+            query_budget: None,
+            transport: None,
+            decode_mode: DecodeMode::default(),
+            on_warning: None,
+            credentials_provider: None,
+            on_token_refreshed: None,
+            log_sensitive_data: false,
+            #[cfg(feature = "cache")]
+            cache: None,
+        }
+    }
+
+    /// Scheme and host of the Sigma server, defaults to `https://sigma-search.io`
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Path prefix of the versioned API, defaults to `/api/v2`
+    pub fn api_path(mut self, api_path: &str) -> Self {
+        self.api_path = api_path.to_string();
+        self
+    }
+
+    /// Total timeout for every request, from connecting until the body has been read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for the connect phase only
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Sends every request (HTTP and HTTPS) through the given proxy URL
+    pub fn proxy(mut self, proxy_url: &str) -> Self {
+        self.proxy = Some(proxy_url.to_string());
+        self
+    }
+
+    /// Trusts an extra PEM encoded CA certificate on top of the system roots
+    pub fn add_root_certificate_pem(mut self, pem: &[u8]) -> Self {
+        self.root_certificates
+            .push(RootCertificate::Pem(pem.to_vec()));
+        self
+    }
+
+    /// Trusts an extra DER encoded CA certificate on top of the system roots
+    pub fn add_root_certificate_der(mut self, der: &[u8]) -> Self {
+        self.root_certificates
+            .push(RootCertificate::Der(der.to_vec()));
+        self
+    }
+
+    /// Retries queries that failed with a transient error, disabled by default
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Limits how fast queries are sent
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Refuses queries once the local daily budget of their endpoint is used up
+    pub fn query_budget(mut self, budget: QueryBudget) -> Self {
+        self.query_budget = Some(budget);
+        self
+    }
+
+    /// Sends requests through `transport` instead of the default reqwest client.
+    /// Timeouts, proxy, user agent and certificates are then ignored.
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Strict by default, see `DecodeMode`
+    pub fn decode_mode(mut self, mode: DecodeMode) -> Self {
+        self.decode_mode = mode;
+        self
+    }
+
+    /// Called with every field repaired or dropped while decoding in lenient mode
+    pub fn on_warning<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Warning) + Send + Sync + 'static,
+    {
+        self.on_warning = Some(Arc::new(hook));
+        self
+    }
+
+    /// Logs in again with credentials from `provider` and retries once when
+    /// Sigma rejects the token
+    pub fn credentials_provider<P: CredentialsProvider + 'static>(mut self, provider: P) -> Self {
+        self.credentials_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Called with the new session after logging in again automatically
+    pub fn on_token_refreshed<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Session) + Send + Sync + 'static,
+    {
+        self.on_token_refreshed = Some(Arc::new(hook));
+        self
+    }
+
+    /// Records tokens, passwords and query values in the tracing spans instead
+    /// of redacting them. Only for debugging, the logs then hold personal data.
+    pub fn log_sensitive_data(mut self, enabled: bool) -> Self {
+        self.log_sensitive_data = enabled;
+        self
+    }
+
+    /// Answers repeated queries from `cache` while their responses are fresh
+    #[cfg(feature = "cache")]
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn build(self) -> Result<SigmaClient, Error> {
+        let base_url = normalize_base_url(&self.base_url)?;
+        let api_path = normalize_api_path(&self.api_path);
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => Arc::new(ReqwestTransport::new(build_reqwest_client(
+                self.timeout,
+                self.connect_timeout,
+                self.user_agent,
+                self.proxy,
+                self.root_certificates,
+            )?)),
+        };
+
+        let mut http = HTTPClient::with_transport(transport, &base_url, &api_path);
+        http.set_retry_policy(self.retry_policy);
+        http.set_rate_limiter(self.rate_limiter.map(Arc::new));
+        http.set_query_budget(self.query_budget.map(Arc::new));
+        http.set_decode_mode(self.decode_mode);
+        if let Some(hook) = self.on_warning {
+            http.set_warning_hook(move |warning| hook(warning));
+        }
+        http.set_credentials_provider(self.credentials_provider);
+        http.set_log_sensitive_data(self.log_sensitive_data);
+        #[cfg(feature = "cache")]
+        http.set_cache(self.cache.map(Arc::new));
+        if let Some(hook) = self.on_token_refreshed {
+            http.set_token_refreshed_hook(move |session| hook(session));
+        }
+        Ok(SigmaClient { http })
+    }
+
+    /// Builds a `blocking::SigmaClient` with the same settings
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> Result<crate::blocking::SigmaClient, Error> {
+        crate::blocking::SigmaClient::from_async(self.build()?)
+    }
+}
+
+fn build_reqwest_client(
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    root_certificates: Vec<RootCertificate>,
+) -> Result<reqwest::Client, Error> {
+    let mut http_client = reqwest::Client::builder();
+    if let Some(timeout) = timeout {
+        http_client = http_client.timeout(timeout);
+    }
+    if let Some(timeout) = connect_timeout {
+        http_client = http_client.connect_timeout(timeout);
+    }
+    if let Some(user_agent) = user_agent {
+        http_client = http_client.user_agent(user_agent);
+    }
+    if let Some(proxy_url) = proxy {
+        let proxy = reqwest::Proxy::all(&proxy_url).map_err(|e| {
+            Error::InvalidConfiguration(format!("invalid proxy {}: {}", proxy_url, e))
+        })?;
+        http_client = http_client.proxy(proxy);
+    }
+    for certificate in root_certificates {
+        let certificate = match certificate {
+            RootCertificate::Pem(pem) => reqwest::Certificate::from_pem(&pem),
+            RootCertificate::Der(der) => reqwest::Certificate::from_der(&der),
+        }
+        .map_err(|e| Error::InvalidConfiguration(format!("invalid certificate: {}", e)))?;
+        http_client = http_client.add_root_certificate(certificate);
+    }
+    http_client
+        .build()
+        .map_err(|e| Error::InvalidConfiguration(e.to_string()))
+}
+
+fn normalize_base_url(base_url: &str) -> Result<String, Error> {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    let url = reqwest::Url::parse(trimmed).map_err(|e| {
+        Error::InvalidConfiguration(format!("invalid base URL {}: {}", base_url, e))
+    })?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(Error::InvalidConfiguration(format!(
+            "invalid base URL {}: scheme must be http or https",
+            base_url
+        )));
+    }
+    Ok(trimmed.to_string())
+}
+
+fn normalize_api_path(api_path: &str) -> String {
+    let trimmed = api_path.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
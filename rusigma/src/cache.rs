@@ -0,0 +1,302 @@
+//! Encrypted on-disk cache of query responses, enabled by the `cache` feature
+//!
+//! Every response is stored in its own file, encrypted with ChaCha20-Poly1305
+//! under a key derived from a passphrase with Argon2. File names are keyed
+//! hashes of the query and the URL it is sent to, so the cache directory does
+//! not reveal which DNIs, plates or phones were looked up and servers never
+//! share entries.
+
+use crate::endpoints::Endpoint;
+use crate::errors::Error;
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// No entry is kept longer than this, whatever the configured TTLs
+pub const MAX_RETENTION: Duration = Duration::from_secs(24 * 3600);
+pub const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+const SALT_FILE: &str = "salt";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const ENTRY_EXTENSION: &str = "entry";
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Seconds since the Unix epoch
+    stored_at: u64,
+    response: Value,
+}
+
+/// Responses of previous queries, valid for a TTL per endpoint
+///
+/// Hits are answered without consuming quota, budget or rate limit, and
+/// without the `extra` fields of the original response. Entries past the
+/// maximum retention are deleted when the cache is opened and every time a
+/// response is stored.
+pub struct ResponseCache {
+    dir: PathBuf,
+    cipher: ChaCha20Poly1305,
+    index_key: [u8; 32],
+    ttls: HashMap<Endpoint, Duration>,
+    default_ttl: Duration,
+    max_retention: Duration,
+    refresh: bool,
+}
+
+/// Hides the keys
+impl fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("dir", &self.dir)
+            .field("ttls", &self.ttls)
+            .field("default_ttl", &self.default_ttl)
+            .field("max_retention", &self.max_retention)
+            .field("refresh", &self.refresh)
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    /// Opens the cache in `dir`, creating it if needed, with the key derived
+    /// from `passphrase`. Entries written with another passphrase are misses.
+    pub fn open<P: AsRef<Path>>(dir: P, passphrase: &str) -> Result<ResponseCache, Error> {
+        let dir = dir.as_ref().to_path_buf();
+        create_private_dir(&dir)?;
+        let salt = read_or_create_salt(&dir)?;
+        let mut keys = [0u8; 64];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut keys)
+            .map_err(|e| Error::CacheStorage(format!("could not derive the key: {}", e)))?;
+        let mut index_key = [0u8; 32];
+        index_key.copy_from_slice(&keys[32..]);
+        let cache = ResponseCache {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&keys[..32])),
+            dir,
+            index_key,
+            ttls: HashMap::new(),
+            default_ttl: DEFAULT_TTL,
+            max_retention: MAX_RETENTION,
+            refresh: false,
+        };
+        cache.prune()?;
+        Ok(cache)
+    }
+
+    /// How long responses of `endpoint` are reused
+    pub fn ttl(mut self, endpoint: Endpoint, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint, ttl);
+        self
+    }
+
+    /// TTL of endpoints without their own, 15 minutes by default
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Age after which entries are deleted, at most `MAX_RETENTION`
+    pub fn max_retention(mut self, retention: Duration) -> Self {
+        self.max_retention = retention.min(MAX_RETENTION);
+        self
+    }
+
+    /// Ignores stored responses but still stores the new ones
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Effective TTL of `endpoint`, never longer than the maximum retention
+    pub fn ttl_for(&self, endpoint: Endpoint) -> Duration {
+        self.ttls
+            .get(&endpoint)
+            .copied()
+            .unwrap_or(self.default_ttl)
+            .min(self.max_retention)
+    }
+
+    /// Stored response of the query, if any and still fresh
+    pub(crate) fn get(&self, endpoint: Endpoint, url: &str, request: &Value) -> Option<Value> {
+        if self.refresh {
+            return None;
+        }
+        let path = self.entry_path(endpoint, url, request);
+        let data = fs::read(&path).ok()?;
+        let entry = match self.decrypt(&data) {
+            Some(entry) => entry,
+            None => {
+                tracing::debug!(endpoint = endpoint.name(), "unreadable cache entry");
+                return None;
+            }
+        };
+        if now().saturating_sub(entry.stored_at) >= self.ttl_for(endpoint).as_secs() {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        Some(entry.response)
+    }
+
+    /// Stores the response of the query and prunes old entries, failures
+    /// only leave the query uncached
+    pub(crate) fn put(&self, endpoint: Endpoint, url: &str, request: &Value, response: Value) {
+        if let Err(error) = self.prune() {
+            tracing::warn!(%error, "could not prune the cache");
+        }
+        if self.ttl_for(endpoint).is_zero() {
+            return;
+        }
+        let entry = Entry {
+            stored_at: now(),
+            response,
+        };
+        let path = self.entry_path(endpoint, url, request);
+        let result = self
+            .encrypt(&entry)
+            .and_then(|data| write_private(&path, &data));
+        if let Err(error) = result {
+            tracing::warn!(endpoint = endpoint.name(), %error, "could not cache the response");
+        }
+    }
+
+    /// Deletes the entries older than the maximum retention, returns how many
+    pub fn prune(&self) -> Result<usize, Error> {
+        let mut removed = 0;
+        for path in self.entries()? {
+            let age = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .unwrap_or(Duration::MAX);
+            if age >= self.max_retention {
+                fs::remove_file(&path).map_err(|e| storage_error(&path, e))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Deletes every entry and the key salt
+    pub fn purge(&self) -> Result<(), Error> {
+        purge(&self.dir)
+    }
+
+    fn entries(&self) -> Result<Vec<PathBuf>, Error> {
+        let read_dir = fs::read_dir(&self.dir).map_err(|e| storage_error(&self.dir, e))?;
+        Ok(read_dir
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == ENTRY_EXTENSION))
+            .collect())
+    }
+
+    /// File of a query, named after a hash keyed with the passphrase. The URL
+    /// holds the base URL, API path and plan.
+    fn entry_path(&self, endpoint: Endpoint, url: &str, request: &Value) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(self.index_key);
+        hasher.update(endpoint.name());
+        hasher.update([0]);
+        hasher.update(url);
+        hasher.update([0]);
+        // Object keys are sorted, so equal requests give the same text
+        hasher.update(request.to_string());
+        let name: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        self.dir.join(format!("{}.{}", name, ENTRY_EXTENSION))
+    }
+
+    fn encrypt(&self, entry: &Entry) -> Result<Vec<u8>, Error> {
+        let plaintext = serde_json::to_vec(entry)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| Error::CacheStorage("could not encrypt the entry".to_string()))?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    fn decrypt(&self, data: &[u8]) -> Option<Entry> {
+        if data.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .ok()?;
+        serde_json::from_slice(&plaintext).ok()
+    }
+}
+
+/// Deletes a cache directory, which needs no passphrase
+pub fn purge<P: AsRef<Path>>(dir: P) -> Result<(), Error> {
+    let dir = dir.as_ref();
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(storage_error(dir, e)),
+        _ => Ok(()),
+    }
+}
+
+fn read_or_create_salt(dir: &Path) -> Result<Vec<u8>, Error> {
+    let path = dir.join(SALT_FILE);
+    match fs::read(&path) {
+        Ok(salt) if salt.len() == SALT_LEN => Ok(salt),
+        Ok(_) => Err(storage_error(&path, "invalid salt")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let mut salt = vec![0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            write_private(&path, &salt)?;
+            Ok(salt)
+        }
+        Err(e) => Err(storage_error(&path, e)),
+    }
+}
+
+/// Creates `dir` readable only by the current user
+fn create_private_dir(dir: &Path) -> Result<(), Error> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir).map_err(|e| storage_error(dir, e))
+}
+
+/// Writes a file readable only by the current user
+fn write_private(path: &Path, data: &[u8]) -> Result<(), Error> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(data))
+        .map_err(|e| storage_error(path, e))
+}
+
+fn storage_error<E: fmt::Display>(path: &Path, e: E) -> Error {
+    Error::CacheStorage(format!("{}: {}", path.display(), e))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
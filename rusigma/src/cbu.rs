@@ -0,0 +1,209 @@
+use crate::errors::Error;
+use std::fmt;
+use std::str::FromStr;
+
+const BLOCK1_WEIGHTS: [u32; 7] = [7, 1, 3, 9, 7, 1, 3];
+const BLOCK2_WEIGHTS: [u32; 13] = [3, 9, 7, 1, 3, 9, 7, 1, 3, 9, 7, 1, 3];
+
+/// Entity codes of the most common banks, from the BCRA registry
+const BANKS: &[(&str, &str)] = &[
+    ("007", "Banco de Galicia"),
+    ("011", "Banco de la Nacion Argentina"),
+    ("014", "Banco de la Provincia de Buenos Aires"),
+    ("015", "ICBC"),
+    ("016", "Citibank"),
+    ("017", "BBVA"),
+    ("020", "Banco de la Provincia de Cordoba"),
+    ("027", "Banco Supervielle"),
+    ("029", "Banco de la Ciudad de Buenos Aires"),
+    ("034", "Banco Patagonia"),
+    ("044", "Banco Hipotecario"),
+    ("045", "Banco de San Juan"),
+    ("072", "Banco Santander"),
+    ("083", "Banco del Chubut"),
+    ("086", "Banco de Santa Cruz"),
+    ("093", "Banco de La Pampa"),
+    ("094", "Banco de Corrientes"),
+    ("097", "Banco Provincia del Neuquen"),
+    ("143", "Brubank"),
+    ("150", "HSBC"),
+    ("191", "Banco Credicoop"),
+    ("259", "Banco Itau"),
+    ("268", "Banco Provincia de Tierra del Fuego"),
+    ("285", "Banco Macro"),
+    ("299", "Banco Comafi"),
+    ("322", "Banco Industrial"),
+    ("330", "Nuevo Banco de Santa Fe"),
+    ("386", "Nuevo Banco de Entre Rios"),
+    ("389", "Banco Columbia"),
+];
+
+/// 22 digit bank account (CBU) or virtual account (CVU) number, with both
+/// block check digits verified
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cbu(String);
+
+impl Cbu {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Virtual accounts of payment providers start with `000`
+    pub fn is_cvu(&self) -> bool {
+        self.0.starts_with("000")
+    }
+
+    /// Code of the bank (3 digits) or, for a CVU, of the payment provider (4 digits)
+    pub fn entity_code(&self) -> &str {
+        if self.is_cvu() {
+            &self.0[3..7]
+        } else {
+            &self.0[..3]
+        }
+    }
+
+    /// Name of the bank, if it is a known one
+    pub fn bank_name(&self) -> Option<&'static str> {
+        if self.is_cvu() {
+            return None;
+        }
+        BANKS
+            .iter()
+            .find(|(code, _)| *code == self.entity_code())
+            .map(|(_, name)| *name)
+    }
+}
+
+impl fmt::Display for Cbu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Cbu {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Error::InvalidInput(format!(
+                "CBU {} must only contain digits",
+                s
+            )));
+        }
+        if digits.len() != 22 {
+            return Err(Error::InvalidInput(format!(
+                "CBU {} must have 22 digits, it has {}",
+                s,
+                digits.len()
+            )));
+        }
+        if !check_block(&digits[..8], &BLOCK1_WEIGHTS) {
+            return Err(Error::InvalidInput(format!(
+                "CBU {} has a wrong check digit in the entity and branch block",
+                s
+            )));
+        }
+        if !check_block(&digits[8..], &BLOCK2_WEIGHTS) {
+            return Err(Error::InvalidInput(format!(
+                "CBU {} has a wrong check digit in the account block",
+                s
+            )));
+        }
+        Ok(Cbu(digits))
+    }
+}
+
+/// Alias of a CBU or CVU: 6 to 20 letters, digits, dots or dashes
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Alias(String);
+
+impl Alias {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Alias {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Alias {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let alias = s.trim();
+        let length = alias.chars().count();
+        if !(6..=20).contains(&length) {
+            return Err(Error::InvalidInput(format!(
+                "alias {} must have 6 to 20 characters, it has {}",
+                alias, length
+            )));
+        }
+        if let Some(c) = alias
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || *c == '.' || *c == '-'))
+        {
+            return Err(Error::InvalidInput(format!(
+                "alias {} contains {:?}, only letters, digits, dots and dashes are allowed",
+                alias, c
+            )));
+        }
+        Ok(Alias(alias.to_string()))
+    }
+}
+
+/// Argument of `search_cbu`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CbuOrAlias {
+    Cbu(Cbu),
+    Alias(Alias),
+}
+
+impl fmt::Display for CbuOrAlias {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CbuOrAlias::Cbu(cbu) => cbu.fmt(f),
+            CbuOrAlias::Alias(alias) => alias.fmt(f),
+        }
+    }
+}
+
+/// Input made only of digits is parsed as a CBU, anything else as an alias
+impl FromStr for CbuOrAlias {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if !compact.is_empty() && compact.chars().all(|c| c.is_ascii_digit()) {
+            s.parse().map(CbuOrAlias::Cbu)
+        } else {
+            s.parse().map(CbuOrAlias::Alias)
+        }
+    }
+}
+
+impl From<Cbu> for CbuOrAlias {
+    fn from(cbu: Cbu) -> Self {
+        CbuOrAlias::Cbu(cbu)
+    }
+}
+
+impl From<Alias> for CbuOrAlias {
+    fn from(alias: Alias) -> Self {
+        CbuOrAlias::Alias(alias)
+    }
+}
+
+/// Checks the last digit of `block` against the weighted sum of the others
+fn check_block(block: &str, weights: &[u32]) -> bool {
+    let digits: Vec<u32> = block.chars().filter_map(|c| c.to_digit(10)).collect();
+    let (check, body) = match digits.split_last() {
+        Some(split) => split,
+        None => return false,
+    };
+    let sum: u32 = body.iter().zip(weights).map(|(d, w)| d * w).sum();
+    (10 - sum % 10) % 10 == *check
+}
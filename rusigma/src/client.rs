@@ -1,13 +1,33 @@
+use crate::builder::SigmaClientBuilder;
+use crate::cbu::CbuOrAlias;
+use crate::document::{Dni, Gender};
+use crate::endpoints::spec;
 use crate::errors::Error;
-use crate::http::HTTPClient;
+use crate::filters::NameSearchFilters;
+use crate::http::{HTTPClient, Session};
+use crate::phone::PhoneNumber;
+use crate::plan::Plan;
+use crate::plate::Plate;
+use crate::request::{
+    AddressRequest, DatoRequest, DniRequest, LeaksRequest, NameRequest, PhoneRequest, PlateRequest,
+};
 use crate::response;
 
-use std::collections::HashMap;
-
+/// Sigma API client
+///
+/// All queries take `&self`, the client can be cloned or shared through an
+/// `Arc` between tasks, and clones share the session and connection pool.
+#[derive(Clone)]
 pub struct SigmaClient {
     pub http: HTTPClient,
 }
 
+impl Default for SigmaClient {
+    fn default() -> Self {
+        SigmaClient::new()
+    }
+}
+
 impl SigmaClient {
     pub fn new() -> SigmaClient {
         SigmaClient {
@@ -15,125 +35,153 @@ impl SigmaClient {
         }
     }
 
-    pub fn from_creds(credentials: String) -> SigmaClient {
-        let mut client = SigmaClient::new();
-        let split: Vec<&str> = credentials.split(':').collect();
-        client
-            .http
-            .set_api_token(split[0], split[1].parse::<usize>().unwrap());
-        client
+    /// Returns a builder to configure the base URL, API prefix and HTTP settings
+    pub fn builder() -> SigmaClientBuilder {
+        SigmaClientBuilder::new()
     }
 
-    pub async fn login_with_credentials(
-        &mut self,
-        username: String,
-        password: String,
-    ) -> Result<(), Error> {
+    /// Creates a client from a `token:plan` string, the plan given by ID or
+    /// name. Without plan every query fails until one is set.
+    pub fn from_creds(credentials: String) -> Result<SigmaClient, Error> {
+        let credentials = credentials.trim();
+        let (token, plan) = match credentials.split_once(':') {
+            Some((token, plan)) => (token.trim(), Some(plan.parse::<Plan>()?)),
+            None => (credentials, None),
+        };
+        if token.is_empty() || token.contains(char::is_whitespace) {
+            return Err(Error::InvalidConfiguration(
+                "credentials must have the form token:plan".to_string(),
+            ));
+        }
+        let client = SigmaClient::new();
+        client.http.set_session(Session {
+            token: Some(token.to_string()),
+            plan,
+        });
+        Ok(client)
+    }
+}
+
+/// Defines every query once: as an async method of `SigmaClient` and, with
+/// the `blocking` feature, as a method of `blocking::SigmaClient` running it
+/// to completion, so both clients always offer the same queries
+macro_rules! queries {
+    ($(
+        $(#[$attr:meta])*
+        fn $name:ident(&$self:ident $(, $arg:ident: $ty:ty)* $(,)?) -> $ret:ty $body:block
+    )*) => {
+        impl SigmaClient {
+            $(
+                $(#[$attr])*
+                pub async fn $name(&$self $(, $arg: $ty)*) -> Result<$ret, Error> $body
+            )*
+        }
+
+        #[cfg(feature = "blocking")]
+        impl crate::blocking::SigmaClient {
+            $(
+                $(#[$attr])*
+                pub fn $name(&self $(, $arg: $ty)*) -> Result<$ret, Error> {
+                    self.runtime.block_on(self.inner.$name($($arg),*))
+                }
+            )*
+        }
+    };
+}
+
+queries! {
+    fn login_with_credentials(&self, username: String, password: String) -> () {
         self.http.login_with_credentials(username, password).await?;
         Ok(())
     }
 
     /// Standard - Medium - Profesional
-    pub async fn search_standard_dni(
-        &mut self,
-        dni: String,
-    ) -> Result<response::DNIStandardResponse, Error> {
-        Ok(self.http.get_data_from_dni(dni).await?)
+    fn search_standard_dni(&self, dni: Dni) -> response::DNIStandardResponse {
+        self.http
+            .execute::<spec::StandardDni>(&DniRequest { dni })
+            .await
     }
 
-    pub async fn search_phones_by_dni(
-        &mut self,
-        dni: String,
-    ) -> Result<Vec<response::PhoneNumber>, Error> {
-        Ok(self.http.get_phones_from_dni(dni).await?)
+    fn search_phones_by_dni(&self, dni: Dni) -> Vec<response::PhoneRecord> {
+        self.http
+            .execute::<spec::PhonesByDni>(&DniRequest { dni })
+            .await
     }
 
     /// Medium - Profesional
-    pub async fn search_plate(
-        &mut self,
-        plate: String,
-    ) -> Result<Vec<response::PlateHistory>, Error> {
-        Ok(self.http.get_plate(plate).await?)
+    fn search_plate(&self, plate: Plate) -> Vec<response::PlateHistory> {
+        self.http
+            .execute::<spec::Plate>(&PlateRequest { patente: plate })
+            .await
     }
 
-    pub async fn search_plate_by_dni(
-        &mut self,
-        dni: String,
-    ) -> Result<Vec<response::PlateHistory>, Error> {
-        Ok(self.http.get_plate_from_dni(dni).await?)
+    fn search_plate_by_dni(&self, dni: Dni) -> Vec<response::PlateHistory> {
+        self.http
+            .execute::<spec::PlateByDni>(&DniRequest { dni })
+            .await
     }
 
-    pub async fn search_leaks(
-        &mut self,
-        query: String,
-    ) -> Result<Vec<response::BreachCredentials>, Error> {
-        Ok(self.http.get_query_data_breach(query).await?)
+    fn search_leaks(&self, query: String) -> Vec<response::BreachCredentials> {
+        self.http
+            .execute::<spec::Leaks>(&LeaksRequest { query })
+            .await
     }
 
     /// Profesional
-    pub async fn search_profesional_dni(
-        &mut self,
-        dni: String,
-        gender: usize,
-    ) -> Result<response::DNIProfesional, Error> {
-        let gender_str = match gender {
-            1 => "Masculino",
-            2 => "Femenino",
-            _ => "Otro",
-        };
-        Ok(self
-            .http
-            .get_data_from_dni_profesional(dni, gender_str.to_string())
-            .await?)
+    fn search_profesional_dni(&self, dni: Dni, gender: Gender) -> response::DNIProfesional {
+        let dato = format!("{}:{}", dni, gender);
+        self.http
+            .execute::<spec::ProfesionalDni>(&DatoRequest { dato })
+            .await
     }
 
-    pub async fn search_name(
-        &mut self,
+    fn search_name(
+        &self,
         name: String,
-        params: Option<HashMap<&str, String>>,
-    ) -> Result<Vec<response::PersonaNombre>, Error> {
-        Ok(self.http.get_names(name, params).await?)
+        filters: Option<NameSearchFilters>,
+    ) -> Vec<response::PersonaNombre> {
+        self.http
+            .execute::<spec::Name>(&NameRequest {
+                nombre: name,
+                filtros: filters.unwrap_or_default(),
+            })
+            .await
     }
 
-    pub async fn search_movistar_email(
-        &mut self,
-        number: String,
-    ) -> Result<response::MovistarEmail, Error> {
-        Ok(self.http.get_movistar_email(number).await?)
+    fn search_movistar_email(&self, number: PhoneNumber) -> response::MovistarEmail {
+        self.http
+            .execute::<spec::MovistarEmail>(&PhoneRequest { num: number })
+            .await
     }
 
-    pub async fn search_by_address(
-        &mut self,
-        address: String,
-    ) -> Result<Vec<response::PersonaDireccion>, Error> {
-        Ok(self.http.get_people_by_address(address).await?)
+    fn search_by_address(&self, address: String) -> Vec<response::PersonaDireccion> {
+        self.http
+            .execute::<spec::Address>(&AddressRequest { direccion: address })
+            .await
     }
 
-    pub async fn search_phone(
-        &mut self,
-        number: String,
-    ) -> Result<Vec<response::PersonaFromNumero>, Error> {
-        Ok(self.http.get_data_by_number(number).await?)
+    fn search_phone(&self, number: PhoneNumber) -> Vec<response::PersonaFromNumero> {
+        self.http
+            .execute::<spec::Phone>(&PhoneRequest { num: number })
+            .await
     }
 
-    pub async fn search_phone_magic(
-        &mut self,
-        number: String,
-    ) -> Result<response::PersonaFromNumeroMagic, Error> {
-        Ok(self.http.get_data_by_number_magic(number).await?)
+    fn search_phone_magic(&self, number: PhoneNumber) -> response::PersonaFromNumeroMagic {
+        self.http
+            .execute::<spec::PhoneMagic>(&DatoRequest {
+                dato: number.to_string(),
+            })
+            .await
     }
 
-    pub async fn search_cbu(
-        &mut self,
-        cvu_or_alias: String,
-    ) -> Result<response::TitularCBU, Error> {
-        Ok(self.http.get_data_by_cvu(cvu_or_alias).await?)
+    fn search_cbu(&self, cbu_or_alias: impl Into<CbuOrAlias>) -> response::TitularCBU {
+        let dato = cbu_or_alias.into().to_string();
+        self.http.execute::<spec::Cbu>(&DatoRequest { dato }).await
     }
 
-    pub async fn search_email(
-        &mut self,
-        email: String,
-    ) -> Result<response::EmailResultados, Error> {
-        Ok(self.http.get_data_by_email(email).await?)
+    fn search_email(&self, email: String) -> response::EmailResultados {
+        self.http
+            .execute::<spec::Email>(&DatoRequest { dato: email })
+            .await
     }
 }
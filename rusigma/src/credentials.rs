@@ -0,0 +1,172 @@
+//! Sources of the username and password used to log in again when the token
+//! expires, see `SigmaClientBuilder::credentials_provider`
+
+use crate::errors::Error;
+use async_trait::async_trait;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_USERNAME_VAR: &str = "SIGMA_USERNAME";
+pub const DEFAULT_PASSWORD_VAR: &str = "SIGMA_PASSWORD";
+
+/// Username and password of a Sigma account
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Credentials {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+}
+
+/// Hides the password
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+/// Supplies credentials when the client has to log in again
+#[async_trait]
+pub trait CredentialsProvider: Send + Sync {
+    async fn credentials(&self) -> Result<Credentials, Error>;
+}
+
+/// Always the same credentials
+#[async_trait]
+impl CredentialsProvider for Credentials {
+    async fn credentials(&self) -> Result<Credentials, Error> {
+        Ok(self.clone())
+    }
+}
+
+/// A closure returning the credentials, e.g. one asking for them on the terminal
+#[async_trait]
+impl<F> CredentialsProvider for F
+where
+    F: Fn() -> Result<Credentials, Error> + Send + Sync,
+{
+    async fn credentials(&self) -> Result<Credentials, Error> {
+        self()
+    }
+}
+
+/// Reads the credentials from environment variables, `SIGMA_USERNAME` and
+/// `SIGMA_PASSWORD` by default
+#[derive(Debug, Clone)]
+pub struct EnvCredentials {
+    username_var: String,
+    password_var: String,
+}
+
+impl Default for EnvCredentials {
+    fn default() -> Self {
+        EnvCredentials::new(DEFAULT_USERNAME_VAR, DEFAULT_PASSWORD_VAR)
+    }
+}
+
+impl EnvCredentials {
+    pub fn new(username_var: &str, password_var: &str) -> Self {
+        EnvCredentials {
+            username_var: username_var.to_string(),
+            password_var: password_var.to_string(),
+        }
+    }
+
+    /// Whether both variables are set
+    pub fn is_available(&self) -> bool {
+        std::env::var_os(&self.username_var).is_some()
+            && std::env::var_os(&self.password_var).is_some()
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for EnvCredentials {
+    async fn credentials(&self) -> Result<Credentials, Error> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|e| {
+                Error::CredentialsUnavailable(format!("environment variable {}: {}", name, e))
+            })
+        };
+        Ok(Credentials::new(
+            var(&self.username_var)?,
+            var(&self.password_var)?,
+        ))
+    }
+}
+
+/// Reads the credentials from a file with `username=` and `password=` lines.
+/// Blank lines and lines starting with `#` are ignored, and files other
+/// users can read are refused.
+#[derive(Debug, Clone)]
+pub struct FileCredentials {
+    path: PathBuf,
+}
+
+impl FileCredentials {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileCredentials { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for FileCredentials {
+    async fn credentials(&self) -> Result<Credentials, Error> {
+        let unavailable = |reason: String| {
+            Error::CredentialsUnavailable(format!("{}: {}", self.path.display(), reason))
+        };
+        check_permissions(&self.path).map_err(unavailable)?;
+        let contents = fs::read_to_string(&self.path).map_err(|e| unavailable(e.to_string()))?;
+        let (mut username, mut password) = (None, None);
+        for (number, line) in contents.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+            {
+                Some(("username", value)) => username = Some(value.to_string()),
+                Some(("password", value)) => password = Some(value.to_string()),
+                // The line is not quoted, it may hold the password
+                _ => return Err(unavailable(format!("invalid line {}", number + 1))),
+            }
+        }
+        match (username, password) {
+            (Some(username), Some(password)) => Ok(Credentials { username, password }),
+            _ => Err(unavailable("username or password missing".to_string())),
+        }
+    }
+}
+
+/// Fails if the group or other users can read or modify the file
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path)
+        .map_err(|e| e.to_string())?
+        .permissions()
+        .mode();
+    if mode & 0o077 != 0 {
+        return Err(format!(
+            "other users can read this file, run chmod 600 {}",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,356 @@
+use crate::endpoints::Endpoint;
+use crate::errors::{body_snippet, Error};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Upper bound on the fixes applied to one body before giving up
+const MAX_REPAIRS: usize = 256;
+
+/// Reason of the `FieldDropped` warnings of blank strings
+const BLANK: &str = "blank string read as missing";
+
+/// How strictly response bodies must match the response types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// Empty strings of optional fields are read as missing values, numbers and strings are
+    /// converted into each other when the type asks for it, and optional
+    /// values that still do not fit are dropped with a `Warning`
+    Lenient,
+    /// The body must match the response type exactly
+    #[default]
+    Strict,
+}
+
+/// Something in a response body that did not match its type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// The value was converted, e.g. the number `45` into the string `"45"`
+    FieldCoerced { path: String, value: String },
+    /// The value could not be used and was read as missing
+    FieldDropped {
+        path: String,
+        value: String,
+        reason: String,
+    },
+    /// The body has fields the response type does not know about, kept in
+    /// its `extra` map, or lacks fields the type has. Array elements are
+    /// written `[]`, e.g. `cobertura[].nombre`.
+    SchemaDrift {
+        /// `None` for the login response
+        endpoint: Option<Endpoint>,
+        added: Vec<String>,
+        missing: Vec<String>,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::FieldCoerced { path, value } => {
+                write!(f, "field {} converted from {}", path, value)
+            }
+            Warning::FieldDropped {
+                path,
+                value,
+                reason,
+            } => write!(
+                f,
+                "field {} dropped, {} was rejected: {}",
+                path, value, reason
+            ),
+            Warning::SchemaDrift {
+                endpoint,
+                added,
+                missing,
+            } => {
+                match endpoint {
+                    Some(endpoint) => write!(f, "schema drift in {}", endpoint)?,
+                    None => write!(f, "schema drift in login")?,
+                }
+                if !added.is_empty() {
+                    write!(f, ", added fields: {}", added.join(", "))?;
+                }
+                if !missing.is_empty() {
+                    write!(f, ", missing fields: {}", missing.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub(crate) type WarningHook = Arc<dyn Fn(&Warning) + Send + Sync>;
+
+/// Decoding settings shared by every response of a client
+#[derive(Clone, Default)]
+pub(crate) struct Decoder {
+    pub(crate) mode: DecodeMode,
+    pub(crate) on_warning: Option<WarningHook>,
+}
+
+impl fmt::Debug for Decoder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Decoder")
+            .field("mode", &self.mode)
+            .field("on_warning", &self.on_warning.is_some())
+            .finish()
+    }
+}
+
+impl Decoder {
+    /// Decodes the body of a response to `endpoint`, reporting warnings to the hook
+    pub(crate) fn decode<T>(&self, body: &[u8], endpoint: Option<Endpoint>) -> Result<T, Error>
+    where
+        T: DeserializeOwned + Serialize,
+    {
+        let error = |e: &dyn fmt::Display| {
+            Error::DeserealizationError(format!("{} in body: {}", e, body_snippet(body)))
+        };
+        if self.mode == DecodeMode::Strict {
+            let result: T = serde_json::from_slice(body).map_err(|e| error(&e))?;
+            if self.on_warning.is_some() {
+                if let Ok(value) = serde_json::from_slice::<Value>(body) {
+                    self.report(&Vec::from_iter(drift(endpoint, &value, &result)));
+                }
+            }
+            return Ok(result);
+        }
+
+        let mut value: Value = serde_json::from_slice(body).map_err(|e| error(&e))?;
+        let mut warnings = Vec::new();
+        let mut blanks = Vec::new();
+        blank_to_null(&mut value, "", "", &mut warnings, &mut blanks);
+        for _ in 0..MAX_REPAIRS {
+            let e = match serde_path_to_error::deserialize::<_, T>(&value) {
+                Ok(result) => {
+                    if restore_undeclared(&mut value, &result, &blanks, &mut warnings) {
+                        continue;
+                    }
+                    if self.on_warning.is_some() {
+                        warnings.extend(drift(endpoint, &value, &result));
+                        self.report(&warnings);
+                    }
+                    return Ok(result);
+                }
+                Err(e) => e,
+            };
+            let path = e.path().to_string();
+            let field = match lookup(&mut value, e.path()) {
+                Some(field) => field,
+                None => return Err(error(&format_args!("{} at {}", e.inner(), path))),
+            };
+            if let Some(blank) = restore_blank(field, &mut warnings, &path) {
+                *field = blank;
+                continue;
+            }
+            match repair(field, &warnings, &path) {
+                Some(fix) => {
+                    let mut original = field.to_string();
+                    if fix.is_null() {
+                        // Report the value as sent, not the failed conversion
+                        if let Some(i) = warnings.iter().position(
+                            |w| matches!(w, Warning::FieldCoerced { path: p, .. } if *p == path),
+                        ) {
+                            if let Warning::FieldCoerced { value, .. } = warnings.remove(i) {
+                                original = value;
+                            }
+                        }
+                    }
+                    *field = fix;
+                    warnings.push(if field.is_null() {
+                        Warning::FieldDropped {
+                            path,
+                            value: original,
+                            reason: e.inner().to_string(),
+                        }
+                    } else {
+                        Warning::FieldCoerced {
+                            path,
+                            value: original,
+                        }
+                    });
+                }
+                None => return Err(error(&format_args!("{} at {}", e.inner(), path))),
+            }
+        }
+        Err(error(&"too many invalid fields"))
+    }
+
+    fn report(&self, warnings: &[Warning]) {
+        if let Some(hook) = &self.on_warning {
+            for warning in warnings {
+                hook(warning);
+            }
+        }
+    }
+}
+
+/// Schema drift between a body and the fields of the type it was decoded into.
+/// The `extra` maps are not serialized, so serializing the result again gives
+/// exactly the known fields.
+fn drift<T: Serialize>(endpoint: Option<Endpoint>, body: &Value, result: &T) -> Option<Warning> {
+    let known = serde_json::to_value(result).ok()?;
+    let mut added = BTreeSet::new();
+    let mut missing = BTreeSet::new();
+    compare(body, &known, "", &mut added, &mut missing);
+    if added.is_empty() && missing.is_empty() {
+        return None;
+    }
+    Some(Warning::SchemaDrift {
+        endpoint,
+        added: added.into_iter().collect(),
+        missing: missing.into_iter().collect(),
+    })
+}
+
+fn compare(
+    body: &Value,
+    known: &Value,
+    path: &str,
+    added: &mut BTreeSet<String>,
+    missing: &mut BTreeSet<String>,
+) {
+    let join = |key: &str| match path {
+        "" => key.to_string(),
+        _ => format!("{}.{}", path, key),
+    };
+    match (body, known) {
+        (Value::Object(body), Value::Object(known)) => {
+            for key in body.keys().filter(|key| !known.contains_key(*key)) {
+                added.insert(join(key));
+            }
+            for (key, known) in known {
+                match body.get(key) {
+                    Some(body) => compare(body, known, &join(key), added, missing),
+                    None => {
+                        missing.insert(join(key));
+                    }
+                }
+            }
+        }
+        (Value::Array(body), Value::Array(known)) => {
+            let path = format!("{}[]", path);
+            for (body, known) in body.iter().zip(known) {
+                compare(body, known, &path, added, missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replaces empty or blank strings with `null`, recursively, with a
+/// `FieldDropped` warning for each. Paths are written like the ones of
+/// `serde_path_to_error`, e.g. `[0].empresa`, and `blanks` pairs each with
+/// the JSON pointer of the field.
+fn blank_to_null(
+    value: &mut Value,
+    path: &str,
+    pointer: &str,
+    warnings: &mut Vec<Warning>,
+    blanks: &mut Vec<(String, String)>,
+) {
+    match value {
+        Value::String(s) if s.trim().is_empty() => {
+            warnings.push(Warning::FieldDropped {
+                path: path.to_string(),
+                value: Value::String(std::mem::take(s)).to_string(),
+                reason: BLANK.to_string(),
+            });
+            blanks.push((path.to_string(), pointer.to_string()));
+            *value = Value::Null;
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                let path = format!("{}[{}]", path, index);
+                let pointer = format!("{}/{}", pointer, index);
+                blank_to_null(item, &path, &pointer, warnings, blanks);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                let path = match path {
+                    "" => key.clone(),
+                    _ => format!("{}.{}", path, key),
+                };
+                let pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                blank_to_null(field, &path, &pointer, warnings, blanks);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Puts back the blank strings of keys the type does not declare, which end
+/// up as sent in its `extra` map instead of being reported. Returns whether
+/// any was, so the body is decoded again.
+fn restore_undeclared<T: Serialize>(
+    value: &mut Value,
+    result: &T,
+    blanks: &[(String, String)],
+    warnings: &mut Vec<Warning>,
+) -> bool {
+    let known = match serde_json::to_value(result) {
+        Ok(known) => known,
+        Err(_) => return false,
+    };
+    let mut restored = false;
+    for (path, pointer) in blanks {
+        if known.pointer(pointer).is_some() {
+            continue;
+        }
+        if let Some(field) = value.pointer_mut(pointer) {
+            if let Some(blank) = restore_blank(field, warnings, path) {
+                *field = blank;
+                restored = true;
+            }
+        }
+    }
+    restored
+}
+
+/// The blank string sent for a field that was nulled but is not optional,
+/// which is then kept as sent and no longer reported
+fn restore_blank(field: &Value, warnings: &mut Vec<Warning>, path: &str) -> Option<Value> {
+    if !field.is_null() {
+        return None;
+    }
+    let i = warnings.iter().position(|w| {
+        matches!(w, Warning::FieldDropped { path: p, reason, .. } if p == path && reason == BLANK)
+    })?;
+    match warnings.remove(i) {
+        Warning::FieldDropped { value, .. } => serde_json::from_str(&value).ok(),
+        _ => None,
+    }
+}
+
+fn lookup<'a>(value: &'a mut Value, path: &serde_path_to_error::Path) -> Option<&'a mut Value> {
+    use serde_path_to_error::Segment;
+    path.iter().try_fold(value, |value, segment| match segment {
+        Segment::Seq { index } => value.get_mut(*index),
+        Segment::Map { key } => value.get_mut(key.as_str()),
+        _ => None,
+    })
+}
+
+/// Next value to try for a field rejected by its type: a conversion between
+/// numbers and strings first, then `null`. Objects, arrays and fields already
+/// dropped cannot be repaired.
+fn repair(field: &Value, warnings: &[Warning], path: &str) -> Option<Value> {
+    let coerced = warnings
+        .iter()
+        .any(|w| matches!(w, Warning::FieldCoerced { path: p, .. } if p == path));
+    match field {
+        Value::Number(n) if !coerced => Some(Value::String(n.to_string())),
+        Value::Bool(b) if !coerced => Some(Value::String(b.to_string())),
+        Value::String(s) if !coerced => match s.trim().parse::<serde_json::Number>() {
+            Ok(n) => Some(Value::Number(n)),
+            Err(_) => Some(Value::Null),
+        },
+        Value::Number(_) | Value::Bool(_) | Value::String(_) => Some(Value::Null),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
@@ -0,0 +1,234 @@
+use crate::errors::Error;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Prefixes assigned to people, in the order they are usually tried
+const PERSON_PREFIXES: [u8; 5] = [20, 27, 23, 24, 25];
+/// Prefixes of CUITs valid for people or companies
+const VALID_PREFIXES: [u8; 9] = [20, 23, 24, 25, 26, 27, 30, 33, 34];
+const CHECK_WEIGHTS: [u32; 10] = [5, 4, 3, 2, 7, 6, 5, 4, 3, 2];
+
+/// Gender of a person as registered in the DNI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gender {
+    Male,
+    Female,
+    Other,
+}
+
+impl Gender {
+    /// Name used by Sigma
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Gender::Male => "Masculino",
+            Gender::Female => "Femenino",
+            Gender::Other => "Otro",
+        }
+    }
+}
+
+impl fmt::Display for Gender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the letter of the DNI (`M`, `F`, `X`), the Spanish or English name,
+/// or the legacy codes 1, 2 and 3
+impl FromStr for Gender {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "M" | "MASCULINO" | "HOMBRE" | "MALE" | "1" => Ok(Gender::Male),
+            "F" | "FEMENINO" | "MUJER" | "FEMALE" | "2" => Ok(Gender::Female),
+            "X" | "OTRO" | "OTHER" | "3" => Ok(Gender::Other),
+            _ => Err(Error::InvalidInput(format!("unknown gender {}", s))),
+        }
+    }
+}
+
+impl Serialize for Gender {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Argentine national identity number (DNI), 6 to 8 digits
+///
+/// Parses `30123456` as well as `30.123.456`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Dni(String);
+
+impl Dni {
+    /// Digits of the DNI, without dots or leading zeros
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Possible CUIT/CUIL of the person, most likely first
+    pub fn cuit_candidates(&self, gender: Gender) -> Vec<Cuit> {
+        let prefixes: &[u8] = match gender {
+            Gender::Male => &[20, 23, 24, 25],
+            Gender::Female => &[27, 23, 24, 25],
+            Gender::Other => &PERSON_PREFIXES,
+        };
+        let dni = format!("{:0>8}", self.0);
+        prefixes
+            .iter()
+            .filter_map(|prefix| {
+                let body = format!("{}{}", prefix, dni);
+                check_digit(&body).map(|digit| Cuit(format!("{}{}", body, digit)))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Dni {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Dni {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = strip_separators(s, &['.', ' '])
+            .ok_or_else(|| Error::InvalidInput(format!("DNI {} must only contain digits", s)))?;
+        let digits = digits.trim_start_matches('0');
+        if !(6..=8).contains(&digits.len()) {
+            return Err(Error::InvalidInput(format!(
+                "DNI {} must have 6 to 8 digits",
+                s
+            )));
+        }
+        Ok(Dni(digits.to_string()))
+    }
+}
+
+/// The DNI embedded in the CUIL of a person, companies have none
+impl TryFrom<&Cuit> for Dni {
+    type Error = Error;
+
+    fn try_from(cuit: &Cuit) -> Result<Self, Self::Error> {
+        if cuit.is_company() {
+            return Err(Error::InvalidInput(format!(
+                "CUIT {} belongs to a company, not to a person",
+                cuit
+            )));
+        }
+        cuit.0[2..10].parse()
+    }
+}
+
+impl TryFrom<Cuit> for Dni {
+    type Error = Error;
+
+    fn try_from(cuit: Cuit) -> Result<Self, Self::Error> {
+        Dni::try_from(&cuit)
+    }
+}
+
+impl Serialize for Dni {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Argentine tax (CUIT) or labour (CUIL) identifier with a verified check digit
+///
+/// Parses `20301234564` as well as `20-30123456-4`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cuit(String);
+
+impl Cuit {
+    /// The 11 digits, without dashes
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Type prefix: 20, 23, 24 and 27 for people, 30, 33 and 34 for companies
+    pub fn prefix(&self) -> u8 {
+        self.0[..2].parse().unwrap_or_default()
+    }
+
+    /// DNI of the person, fails for companies and numbers too short for a DNI
+    pub fn dni(&self) -> Result<Dni, Error> {
+        Dni::try_from(self)
+    }
+
+    pub fn is_company(&self) -> bool {
+        self.prefix() >= 30
+    }
+}
+
+/// Formats the CUIT as `20-30123456-4`
+impl fmt::Display for Cuit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}-{}", &self.0[..2], &self.0[2..10], &self.0[10..])
+    }
+}
+
+impl FromStr for Cuit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = strip_separators(s, &['-', '.', ' '])
+            .ok_or_else(|| Error::InvalidInput(format!("CUIT {} must only contain digits", s)))?;
+        if digits.len() != 11 {
+            return Err(Error::InvalidInput(format!(
+                "CUIT {} must have 11 digits",
+                s
+            )));
+        }
+        let prefix: u8 = digits[..2].parse().unwrap_or_default();
+        if !VALID_PREFIXES.contains(&prefix) {
+            return Err(Error::InvalidInput(format!(
+                "CUIT {} has an unknown prefix {}",
+                s, prefix
+            )));
+        }
+        let expected = check_digit(&digits[..10]);
+        let actual = digits[10..].parse::<u32>().ok();
+        if expected != actual {
+            return Err(Error::InvalidInput(format!(
+                "CUIT {} has a wrong check digit",
+                s
+            )));
+        }
+        Ok(Cuit(digits))
+    }
+}
+
+impl Serialize for Cuit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Removes `separators` and fails if anything but ASCII digits remains
+fn strip_separators(s: &str, separators: &[char]) -> Option<String> {
+    let digits: String = s
+        .trim()
+        .chars()
+        .filter(|c| !separators.contains(c))
+        .collect();
+    (!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())).then_some(digits)
+}
+
+/// Mod 11 check digit of the first 10 digits of a CUIT, `None` when the
+/// remainder gives 10 and the prefix cannot be used with this number
+fn check_digit(body: &str) -> Option<u32> {
+    let sum: u32 = body
+        .chars()
+        .zip(CHECK_WEIGHTS)
+        .map(|(c, weight)| c.to_digit(10).unwrap_or(0) * weight)
+        .sum();
+    match 11 - sum % 11 {
+        11 => Some(0),
+        10 => None,
+        digit => Some(digit),
+    }
+}
@@ -0,0 +1,225 @@
+use crate::errors::Error;
+use crate::plan::Plan;
+use crate::request;
+use crate::response;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+const STANDARD_AND_UP: &[Plan] = &[Plan::Standard, Plan::Medium, Plan::Profesional];
+const MEDIUM_AND_UP: &[Plan] = &[Plan::Medium, Plan::Profesional];
+const PROFESIONAL_ONLY: &[Plan] = &[Plan::Profesional];
+
+/// Typed description of a Sigma query, executed with `HTTPClient::execute`
+pub trait EndpointSpec {
+    const ENDPOINT: Endpoint;
+    /// Body sent to Sigma, without the `tipo` of magic queries
+    type Request: Serialize + Send + Sync;
+    type Response: DeserializeOwned + Serialize;
+}
+
+macro_rules! tipo {
+    () => {
+        None
+    };
+    ($tipo:literal) => {
+        Some($tipo)
+    };
+}
+
+/// Declares every endpoint once: the `Endpoint` variant, its registry data
+/// and a marker type in `spec` implementing `EndpointSpec`
+macro_rules! endpoints {
+    ($(
+        $(#[$doc:meta])*
+        $variant:ident {
+            name: $name:literal,
+            path: $path:literal,
+            $(tipo: $tipo:literal,)?
+            plans: $plans:expr,
+            request: $request:ty,
+            response: $response:ty $(,)?
+        }
+    )*) => {
+        /// Every query offered by the Sigma API
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Endpoint {
+            $($(#[$doc])* $variant,)*
+        }
+
+        const ENDPOINT_COUNT: usize = [$($name),*].len();
+
+        impl Endpoint {
+            pub const ALL: [Endpoint; ENDPOINT_COUNT] = [$(Endpoint::$variant),*];
+
+            /// Stable identifier of the endpoint
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Endpoint::$variant => $name,)*
+                }
+            }
+
+            /// Path of the endpoint below `/{plan}`
+            pub fn path(self) -> &'static str {
+                match self {
+                    $(Endpoint::$variant => $path,)*
+                }
+            }
+
+            /// `tipo` sent to `/resolver/magic`, which serves several queries
+            pub fn tipo(self) -> Option<&'static str> {
+                match self {
+                    $(Endpoint::$variant => tipo!($($tipo)?),)*
+                }
+            }
+
+            /// Plans entitled to call this endpoint
+            pub fn allowed_plans(self) -> &'static [Plan] {
+                match self {
+                    $(Endpoint::$variant => $plans,)*
+                }
+            }
+        }
+
+        /// Marker types describing each endpoint, named after its `Endpoint` variant
+        pub mod spec {
+            use super::{request, response, Endpoint, EndpointSpec};
+
+            $(
+                $(#[$doc])*
+                #[derive(Debug, Clone, Copy)]
+                pub struct $variant;
+
+                impl EndpointSpec for $variant {
+                    const ENDPOINT: Endpoint = Endpoint::$variant;
+                    type Request = $request;
+                    type Response = $response;
+                }
+            )*
+        }
+    };
+}
+
+endpoints! {
+    StandardDni {
+        name: "standard_dni",
+        path: "/osint/argentina/resolver/dni",
+        plans: STANDARD_AND_UP,
+        request: request::DniRequest,
+        response: response::DNIStandardResponse,
+    }
+    PhonesByDni {
+        name: "phones_by_dni",
+        path: "/osint/argentina/resolver/dni_celular",
+        plans: STANDARD_AND_UP,
+        request: request::DniRequest,
+        response: Vec<response::PhoneRecord>,
+    }
+    Plate {
+        name: "plate",
+        path: "/osint/argentina/resolver/patente",
+        plans: MEDIUM_AND_UP,
+        request: request::PlateRequest,
+        response: Vec<response::PlateHistory>,
+    }
+    PlateByDni {
+        name: "plate_by_dni",
+        path: "/osint/argentina/resolver/patente_dni",
+        plans: MEDIUM_AND_UP,
+        request: request::DniRequest,
+        response: Vec<response::PlateHistory>,
+    }
+    Leaks {
+        name: "leaks",
+        path: "/osint/argentina/search_engine/data_breach",
+        plans: MEDIUM_AND_UP,
+        request: request::LeaksRequest,
+        response: Vec<response::BreachCredentials>,
+    }
+    /// `dato` is `dni:Genero`
+    ProfesionalDni {
+        name: "profesional_dni",
+        path: "/osint/argentina/resolver/dni_two",
+        plans: PROFESIONAL_ONLY,
+        request: request::DatoRequest,
+        response: response::DNIProfesional,
+    }
+    Name {
+        name: "name",
+        path: "/osint/argentina/resolver/nombre",
+        plans: PROFESIONAL_ONLY,
+        request: request::NameRequest,
+        response: Vec<response::PersonaNombre>,
+    }
+    MovistarEmail {
+        name: "movistar_email",
+        path: "/osint/argentina/resolver/movistar",
+        plans: PROFESIONAL_ONLY,
+        request: request::PhoneRequest,
+        response: response::MovistarEmail,
+    }
+    Address {
+        name: "address",
+        path: "/osint/argentina/resolver/direccion",
+        plans: PROFESIONAL_ONLY,
+        request: request::AddressRequest,
+        response: Vec<response::PersonaDireccion>,
+    }
+    Phone {
+        name: "phone",
+        path: "/osint/argentina/resolver/celular",
+        plans: PROFESIONAL_ONLY,
+        request: request::PhoneRequest,
+        response: Vec<response::PersonaFromNumero>,
+    }
+    PhoneMagic {
+        name: "phone_magic",
+        path: "/osint/argentina/resolver/magic",
+        tipo: "buscar_celular",
+        plans: PROFESIONAL_ONLY,
+        request: request::DatoRequest,
+        response: response::PersonaFromNumeroMagic,
+    }
+    Cbu {
+        name: "cbu",
+        path: "/osint/argentina/resolver/magic",
+        tipo: "buscar_cbu_alias",
+        plans: PROFESIONAL_ONLY,
+        request: request::DatoRequest,
+        response: response::TitularCBU,
+    }
+    Email {
+        name: "email",
+        path: "/osint/argentina/resolver/magic",
+        tipo: "buscar_email",
+        plans: PROFESIONAL_ONLY,
+        request: request::DatoRequest,
+        response: response::EmailResultados,
+    }
+}
+
+impl Endpoint {
+    pub fn is_allowed_for(self, plan: Plan) -> bool {
+        self.allowed_plans().contains(&plan)
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the stable identifier returned by `Endpoint::name`
+impl FromStr for Endpoint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Endpoint::ALL
+            .iter()
+            .find(|endpoint| endpoint.name() == s.trim())
+            .copied()
+            .ok_or_else(|| Error::InvalidConfiguration(format!("unknown endpoint {}", s)))
+    }
+}
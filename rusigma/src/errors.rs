@@ -1,12 +1,144 @@
+use crate::endpoints::Endpoint;
+use crate::plan::Plan;
 use crate::response::ErrorResponse;
 use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Maximum number of characters of a response body kept in an error
+const BODY_SNIPPET_LEN: usize = 200;
 
 #[derive(Debug)]
 pub enum Error {
     NoLoginToken,
-    ApiError(ErrorResponse),
+    /// The token was rejected (HTTP 401), usually because it expired
+    Unauthorized {
+        status: u16,
+        message: String,
+    },
+    /// The account plan is not allowed to use the endpoint (HTTP 403)
+    PlanForbidden {
+        message: String,
+    },
+    /// Too many requests (HTTP 429), `retry_after` comes from the `Retry-After` header
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    /// The query returned no results (HTTP 404)
+    NotFound {
+        message: String,
+    },
+    /// Sigma or a gateway in front of it failed (HTTP 5xx)
+    ServerError {
+        status: u16,
+        message: String,
+    },
+    /// Any other error status with a well-formed Sigma error body
+    ApiError {
+        status: u16,
+        response: ErrorResponse,
+    },
+    /// Any other error status whose body is not a Sigma error, e.g. an HTML page
+    UnexpectedResponse {
+        status: u16,
+        body: String,
+    },
+    Timeout,
+    /// Sigma could not be reached or the connection dropped before the response arrived
+    Connection(String),
+    /// The request could not be built or the response could not be read
     HTTPInternalError(String),
     DeserealizationError(String),
+    InvalidConfiguration(String),
+    UnknownPlan(String),
+    PlanNotAllowed {
+        endpoint: Endpoint,
+        plan: Plan,
+    },
+    /// The local daily budget of the endpoint is used up, nothing was sent
+    BudgetExceeded {
+        endpoint: Endpoint,
+        limit: u32,
+    },
+    /// The usage counters of the local budget could not be read or saved
+    BudgetStorage(String),
+    /// A query parameter was rejected before sending anything
+    InvalidInput(String),
+    /// The credentials provider could not supply a username and password
+    CredentialsUnavailable(String),
+    /// The response cache could not be opened or purged
+    CacheStorage(String),
+}
+
+impl Error {
+    /// Builds the error for a non-2xx response from its status, `Retry-After`
+    /// header and raw body
+    pub(crate) fn from_response(status: u16, retry_after: Option<&str>, body: &[u8]) -> Error {
+        let parsed = serde_json::from_slice::<ErrorResponse>(body).ok();
+        let message = match &parsed {
+            Some(response) => response.message.clone(),
+            None => body_snippet(body),
+        };
+        match status {
+            401 => Error::Unauthorized { status, message },
+            403 => Error::PlanForbidden { message },
+            404 => Error::NotFound { message },
+            429 => Error::RateLimited {
+                retry_after: retry_after.and_then(parse_retry_after),
+                message,
+            },
+            500..=599 => Error::ServerError { status, message },
+            _ => match parsed {
+                Some(response) => Error::ApiError { status, response },
+                None => Error::UnexpectedResponse {
+                    status,
+                    body: message,
+                },
+            },
+        }
+    }
+
+    /// Whether Sigma rejected the token, so logging in again may fix the query
+    pub fn is_auth_expired(&self) -> bool {
+        matches!(self, Error::Unauthorized { .. })
+    }
+
+    /// HTTP status code of the response that caused the error, if any
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Unauthorized { status, .. }
+            | Error::ServerError { status, .. }
+            | Error::ApiError { status, .. }
+            | Error::UnexpectedResponse { status, .. } => Some(*status),
+            Error::PlanForbidden { .. } => Some(403),
+            Error::NotFound { .. } => Some(404),
+            Error::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+}
+
+/// First characters of a body, lossily decoded, for error messages
+pub(crate) fn body_snippet(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    let text = text.trim();
+    match text.char_indices().nth(BODY_SNIPPET_LEN) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Parses a `Retry-After` header given either in seconds or as an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
 }
 
 impl std::error::Error for Error {}
@@ -15,18 +147,89 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> fmt::Result {
         match self {
             Error::NoLoginToken => write!(f, "Not logged in or no valid token supplied"),
-            Error::ApiError(resp) => write!(f, "Error response from Sigma: {:?}", resp),
+            Error::Unauthorized { message, .. } => {
+                write!(f, "Token rejected by Sigma, log in again: {}", message)
+            }
+            Error::PlanForbidden { message } => {
+                write!(f, "Your plan does not allow this query: {}", message)
+            }
+            Error::RateLimited {
+                retry_after: Some(retry_after),
+                message,
+            } => write!(
+                f,
+                "Rate limited by Sigma, retry in {}s: {}",
+                retry_after.as_secs(),
+                message
+            ),
+            Error::RateLimited {
+                retry_after: None,
+                message,
+            } => write!(f, "Rate limited by Sigma: {}", message),
+            Error::NotFound { message } => write!(f, "No results: {}", message),
+            Error::ServerError { status, message } => {
+                write!(f, "Sigma server error (HTTP {}): {}", status, message)
+            }
+            Error::ApiError { status, response } => write!(
+                f,
+                "Error response from Sigma (HTTP {}): {}",
+                status, response.message
+            ),
+            Error::UnexpectedResponse { status, body } => {
+                write!(
+                    f,
+                    "Unexpected response from Sigma (HTTP {}): {}",
+                    status, body
+                )
+            }
+            Error::Timeout => write!(f, "HTTP Request timed out"),
+            Error::Connection(reason) => write!(f, "Could not reach Sigma: {}", reason),
             Error::HTTPInternalError(resp) => write!(f, "HTTP Request failed: {:}", resp),
             Error::DeserealizationError(resp) => {
                 write!(f, "Could not deserialize response: {}", resp)
             }
+            Error::InvalidConfiguration(reason) => {
+                write!(f, "Invalid client configuration: {}", reason)
+            }
+            Error::UnknownPlan(plan) => write!(f, "Unknown Sigma plan: {}", plan),
+            Error::PlanNotAllowed { endpoint, plan } => write!(
+                f,
+                "The {} plan is not allowed to query {}, allowed plans: {}",
+                plan,
+                endpoint,
+                endpoint
+                    .allowed_plans()
+                    .iter()
+                    .map(|p| p.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Error::BudgetExceeded { endpoint, limit } => write!(
+                f,
+                "Daily budget of {} queries to {} used up",
+                limit, endpoint
+            ),
+            Error::BudgetStorage(reason) => {
+                write!(f, "Could not access the query budget: {}", reason)
+            }
+            Error::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+            Error::CredentialsUnavailable(reason) => {
+                write!(f, "Could not get credentials to log in again: {}", reason)
+            }
+            Error::CacheStorage(reason) => write!(f, "Could not access the cache: {}", reason),
         }
     }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Self {
-        Error::HTTPInternalError(e.to_string())
+        if e.is_timeout() {
+            Error::Timeout
+        } else if e.is_connect() || e.is_request() || e.is_body() {
+            Error::Connection(e.to_string())
+        } else {
+            Error::HTTPInternalError(e.to_string())
+        }
     }
 }
 
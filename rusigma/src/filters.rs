@@ -0,0 +1,262 @@
+use crate::errors::Error;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Oldest age accepted by the name search filters
+pub const MAX_AGE: u8 = 120;
+
+/// Argentine province, or the autonomous city of Buenos Aires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Province {
+    BuenosAires,
+    Caba,
+    Catamarca,
+    Chaco,
+    Chubut,
+    Cordoba,
+    Corrientes,
+    EntreRios,
+    Formosa,
+    Jujuy,
+    LaPampa,
+    LaRioja,
+    Mendoza,
+    Misiones,
+    Neuquen,
+    RioNegro,
+    Salta,
+    SanJuan,
+    SanLuis,
+    SantaCruz,
+    SantaFe,
+    SantiagoDelEstero,
+    TierraDelFuego,
+    Tucuman,
+}
+
+impl Province {
+    pub const ALL: [Province; 24] = [
+        Province::BuenosAires,
+        Province::Caba,
+        Province::Catamarca,
+        Province::Chaco,
+        Province::Chubut,
+        Province::Cordoba,
+        Province::Corrientes,
+        Province::EntreRios,
+        Province::Formosa,
+        Province::Jujuy,
+        Province::LaPampa,
+        Province::LaRioja,
+        Province::Mendoza,
+        Province::Misiones,
+        Province::Neuquen,
+        Province::RioNegro,
+        Province::Salta,
+        Province::SanJuan,
+        Province::SanLuis,
+        Province::SantaCruz,
+        Province::SantaFe,
+        Province::SantiagoDelEstero,
+        Province::TierraDelFuego,
+        Province::Tucuman,
+    ];
+
+    /// Name used by Sigma, upper case and without accents
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Province::BuenosAires => "BUENOS AIRES",
+            Province::Caba => "CIUDAD AUTONOMA DE BUENOS AIRES",
+            Province::Catamarca => "CATAMARCA",
+            Province::Chaco => "CHACO",
+            Province::Chubut => "CHUBUT",
+            Province::Cordoba => "CORDOBA",
+            Province::Corrientes => "CORRIENTES",
+            Province::EntreRios => "ENTRE RIOS",
+            Province::Formosa => "FORMOSA",
+            Province::Jujuy => "JUJUY",
+            Province::LaPampa => "LA PAMPA",
+            Province::LaRioja => "LA RIOJA",
+            Province::Mendoza => "MENDOZA",
+            Province::Misiones => "MISIONES",
+            Province::Neuquen => "NEUQUEN",
+            Province::RioNegro => "RIO NEGRO",
+            Province::Salta => "SALTA",
+            Province::SanJuan => "SAN JUAN",
+            Province::SanLuis => "SAN LUIS",
+            Province::SantaCruz => "SANTA CRUZ",
+            Province::SantaFe => "SANTA FE",
+            Province::SantiagoDelEstero => "SANTIAGO DEL ESTERO",
+            Province::TierraDelFuego => "TIERRA DEL FUEGO",
+            Province::Tucuman => "TUCUMAN",
+        }
+    }
+}
+
+impl fmt::Display for Province {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses a province name ignoring case, accents and `-`/`_` separators.
+/// `CABA` and `Capital Federal` are accepted for the city of Buenos Aires.
+impl FromStr for Province {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = normalize(s);
+        match normalized.as_str() {
+            "CABA" | "CAPITAL FEDERAL" | "CIUDAD DE BUENOS AIRES" => return Ok(Province::Caba),
+            _ => {}
+        }
+        Province::ALL
+            .iter()
+            .find(|province| province.as_str() == normalized)
+            .copied()
+            .ok_or_else(|| Error::InvalidInput(format!("unknown province {}", s)))
+    }
+}
+
+impl Serialize for Province {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+fn normalize(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'á' | 'Á' => 'A',
+            'é' | 'É' => 'E',
+            'í' | 'Í' => 'I',
+            'ó' | 'Ó' => 'O',
+            'ú' | 'Ú' | 'ü' | 'Ü' => 'U',
+            '-' | '_' => ' ',
+            c => c.to_ascii_uppercase(),
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Optional filters of a name search
+///
+/// ```
+/// use rusigma::filters::{NameSearchFilters, Province};
+///
+/// let filters = NameSearchFilters::builder()
+///     .province(Province::Cordoba)
+///     .min_age(30)
+///     .max_age(45)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameSearchFilters {
+    #[serde(rename = "provincia_nombre", skip_serializing_if = "Option::is_none")]
+    province: Option<Province>,
+    #[serde(rename = "localidad", skip_serializing_if = "Option::is_none")]
+    locality: Option<String>,
+    #[serde(
+        rename = "edad_desde",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "age_as_string"
+    )]
+    min_age: Option<u8>,
+    #[serde(
+        rename = "edad_hasta",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "age_as_string"
+    )]
+    max_age: Option<u8>,
+}
+
+impl NameSearchFilters {
+    pub fn builder() -> NameSearchFiltersBuilder {
+        NameSearchFiltersBuilder::default()
+    }
+
+    pub fn province(&self) -> Option<Province> {
+        self.province
+    }
+
+    pub fn locality(&self) -> Option<&str> {
+        self.locality.as_deref()
+    }
+
+    pub fn min_age(&self) -> Option<u8> {
+        self.min_age
+    }
+
+    pub fn max_age(&self) -> Option<u8> {
+        self.max_age
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == NameSearchFilters::default()
+    }
+}
+
+/// Sigma expects the ages as strings
+fn age_as_string<S: Serializer>(age: &Option<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+    match age {
+        Some(age) => serializer.serialize_str(&age.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Builds `NameSearchFilters`, checking the age range
+#[derive(Debug, Clone, Default)]
+pub struct NameSearchFiltersBuilder {
+    filters: NameSearchFilters,
+}
+
+impl NameSearchFiltersBuilder {
+    pub fn province(mut self, province: Province) -> Self {
+        self.filters.province = Some(province);
+        self
+    }
+
+    /// City or town, matched by Sigma as given
+    pub fn locality(mut self, locality: &str) -> Self {
+        let locality = locality.trim();
+        self.filters.locality = (!locality.is_empty()).then(|| locality.to_string());
+        self
+    }
+
+    pub fn min_age(mut self, age: u8) -> Self {
+        self.filters.min_age = Some(age);
+        self
+    }
+
+    pub fn max_age(mut self, age: u8) -> Self {
+        self.filters.max_age = Some(age);
+        self
+    }
+
+    /// Fails with `Error::InvalidInput` when an age is over `MAX_AGE` or the
+    /// minimum age is greater than the maximum
+    pub fn build(self) -> Result<NameSearchFilters, Error> {
+        let filters = self.filters;
+        for age in [filters.min_age, filters.max_age].into_iter().flatten() {
+            if age > MAX_AGE {
+                return Err(Error::InvalidInput(format!(
+                    "age {} is over the maximum of {}",
+                    age, MAX_AGE
+                )));
+            }
+        }
+        if let (Some(min), Some(max)) = (filters.min_age, filters.max_age) {
+            if min > max {
+                return Err(Error::InvalidInput(format!(
+                    "minimum age {} is greater than maximum age {}",
+                    min, max
+                )));
+            }
+        }
+        Ok(filters)
+    }
+}
@@ -1,211 +1,494 @@
-extern crate reqwest;
-
+#[cfg(feature = "cache")]
+use crate::cache::ResponseCache;
+use crate::credentials::CredentialsProvider;
+use crate::decode::{DecodeMode, Decoder, Warning};
+use crate::endpoints::{Endpoint, EndpointSpec};
 use crate::errors::Error;
-use crate::response::{
-    BreachCredentials, DNIProfesional, DNIStandardResponse, EmailResultados, ErrorResponse,
-    LoginResponse, MovistarEmail, PersonaDireccion, PersonaFromNumero, PersonaFromNumeroMagic,
-    PersonaNombre, PhoneNumber, PlateHistory, TitularCBU,
-};
+use crate::limits::{QueryBudget, RateLimiter};
+use crate::plan::Plan;
+use crate::response::LoginResponse;
+use crate::retry::{RetryAttempt, RetryPolicy};
+use crate::transport::{Method, ReqwestTransport, Transport, TransportRequest, TransportResponse};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+
+pub const DEFAULT_BASE_URL: &str = "https://sigma-search.io";
+pub const DEFAULT_API_PATH: &str = "/api/v2";
+/// Logged instead of tokens, passwords and query values
+const REDACTED: &str = "[redacted]";
+
+/// Token and plan of the logged in account
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Session {
+    pub token: Option<String>,
+    pub plan: Option<Plan>,
+}
+
+pub(crate) type TokenHook = Arc<dyn Fn(&Session) + Send + Sync>;
+
+/// How to log in again when Sigma rejects the token
+#[derive(Clone, Default)]
+struct Relogin {
+    provider: Option<Arc<dyn CredentialsProvider>>,
+    on_token_refreshed: Option<TokenHook>,
+    /// Held while logging in, shared by the clones of the client
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
 
-const PLAN_LIST: [&str; 5] = ["free", "profesional", "medium", "standard", "comunidades"];
+impl fmt::Debug for Relogin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Relogin")
+            .field("provider", &self.provider.is_some())
+            .field("on_token_refreshed", &self.on_token_refreshed.is_some())
+            .finish()
+    }
+}
 
+/// Low level Sigma client
+///
+/// Clones share the connection pool, the session, the rate limiter and the
+/// budget, so logging in through one clone logs in all of them.
+#[derive(Clone, Debug)]
 pub struct HTTPClient {
-    http_client: reqwest::Client,
-    pub token_str: Option<String>,
-    pub plan_id: Option<usize>,
+    transport: Arc<dyn Transport>,
+    session: Arc<RwLock<Session>>,
     base_url: String,
     base_endpoint: String,
+    pub(crate) retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    query_budget: Option<Arc<QueryBudget>>,
+    decoder: Decoder,
+    relogin: Relogin,
+    log_sensitive_data: bool,
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<ResponseCache>>,
+}
+
+impl Default for HTTPClient {
+    fn default() -> Self {
+        HTTPClient::new()
+    }
 }
 
 impl HTTPClient {
     pub fn new() -> HTTPClient {
-        let http_client = reqwest::Client::new();
+        HTTPClient::with_client(reqwest::Client::new(), DEFAULT_BASE_URL, DEFAULT_API_PATH)
+    }
+
+    /// Creates a client on top of an already configured `reqwest::Client`
+    pub fn with_client(
+        http_client: reqwest::Client,
+        base_url: &str,
+        base_endpoint: &str,
+    ) -> HTTPClient {
+        HTTPClient::with_transport(
+            Arc::new(ReqwestTransport::new(http_client)),
+            base_url,
+            base_endpoint,
+        )
+    }
+
+    /// Creates a client sending its requests through `transport`
+    pub fn with_transport(
+        transport: Arc<dyn Transport>,
+        base_url: &str,
+        base_endpoint: &str,
+    ) -> HTTPClient {
         HTTPClient {
-            http_client,
-            token_str: None,
-            plan_id: None,
-            base_url: "https://sigma-search.io".to_string(),
-            base_endpoint: "/api/v2".to_string(),
+            transport,
+            session: Arc::new(RwLock::new(Session::default())),
+            base_url: base_url.to_string(),
+            base_endpoint: base_endpoint.to_string(),
+            retry_policy: None,
+            rate_limiter: None,
+            query_budget: None,
+            decoder: Decoder::default(),
+            relogin: Relogin::default(),
+            log_sensitive_data: false,
+            #[cfg(feature = "cache")]
+            cache: None,
+        }
+    }
+
+    /// Base URL the client sends requests to, e.g. `https://sigma-search.io`
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// API path prefix placed between the base URL and the plan, e.g. `/api/v2`
+    pub fn base_endpoint(&self) -> &str {
+        &self.base_endpoint
+    }
+
+    /// Retries transient failures of queries according to `policy`
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry_policy = policy;
+    }
+
+    /// Waits for a token of `limiter` before sending every query
+    pub fn set_rate_limiter(&mut self, limiter: Option<Arc<RateLimiter>>) {
+        self.rate_limiter = limiter;
+    }
+
+    /// Counts every query against `budget`, refusing those over the daily limit
+    pub fn set_query_budget(&mut self, budget: Option<Arc<QueryBudget>>) {
+        self.query_budget = budget;
+    }
+
+    pub fn set_decode_mode(&mut self, mode: DecodeMode) {
+        self.decoder.mode = mode;
+    }
+
+    /// Called with every field repaired or dropped while decoding in lenient mode
+    pub fn set_warning_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&Warning) + Send + Sync + 'static,
+    {
+        self.decoder.on_warning = Some(Arc::new(hook));
+    }
+
+    /// Answers repeated queries from `cache` while their responses are fresh
+    #[cfg(feature = "cache")]
+    pub fn set_cache(&mut self, cache: Option<Arc<ResponseCache>>) {
+        self.cache = cache;
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn cache(&self) -> Option<&Arc<ResponseCache>> {
+        self.cache.as_ref()
+    }
+
+    /// Records tokens, passwords, query values and error messages in the
+    /// tracing spans instead of `[redacted]`. Off by default.
+    pub fn set_log_sensitive_data(&mut self, enabled: bool) {
+        self.log_sensitive_data = enabled;
+    }
+
+    /// Text of a trace field holding personal data or secrets
+    fn sensitive(&self, value: &dyn fmt::Display) -> String {
+        if self.log_sensitive_data {
+            value.to_string()
+        } else {
+            REDACTED.to_string()
         }
     }
 
+    /// Logs in again with credentials from `provider` and retries once when a
+    /// query fails because the token expired
+    pub fn set_credentials_provider(&mut self, provider: Option<Arc<dyn CredentialsProvider>>) {
+        self.relogin.provider = provider;
+    }
+
+    /// Called with the new session after logging in again automatically, e.g.
+    /// to save the new token
+    pub fn set_token_refreshed_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&Session) + Send + Sync + 'static,
+    {
+        self.relogin.on_token_refreshed = Some(Arc::new(hook));
+    }
+
+    pub fn query_budget(&self) -> Option<&Arc<QueryBudget>> {
+        self.query_budget.as_ref()
+    }
+
     /// Set API token and plan if you already have one
-    pub fn set_api_token(&mut self, token_str: &str, plan_id: usize) {
-        self.token_str = Some(token_str.to_string());
-        self.plan_id = Some(plan_id)
+    pub fn set_api_token(&self, token_str: &str, plan: Plan) {
+        self.set_session(Session {
+            token: Some(token_str.to_string()),
+            plan: Some(plan),
+        });
     }
 
-    fn get_url(&mut self, path: &str, plan: Option<usize>) -> String {
-        format!(
+    pub fn set_session(&self, session: Session) {
+        *self.session.write().unwrap() = session;
+    }
+
+    pub fn session(&self) -> Session {
+        self.session.read().unwrap().clone()
+    }
+
+    pub fn token(&self) -> Option<String> {
+        self.session.read().unwrap().token.clone()
+    }
+
+    pub fn plan(&self) -> Option<Plan> {
+        self.session.read().unwrap().plan
+    }
+
+    /// Builds the URL of an endpoint for the current plan, failing if the plan
+    /// is not entitled to call it
+    fn get_url(&self, endpoint: Endpoint) -> Result<String, Error> {
+        let plan = self.plan().ok_or(Error::NoLoginToken)?;
+        if !endpoint.is_allowed_for(plan) {
+            return Err(Error::PlanNotAllowed { endpoint, plan });
+        }
+        Ok(format!(
             "{}{}/{}{}",
             self.base_url,
             self.base_endpoint,
-            PLAN_LIST[plan.unwrap_or_else(|| self.plan_id.unwrap())],
-            path
-        )
+            plan.as_str(),
+            endpoint.path()
+        ))
     }
 
-    /// Sends an HTTP request, returns JSON
-    pub async fn request<T: DeserializeOwned>(
+    /// Runs the query described by `E` with `request` as body
+    pub async fn execute<E: EndpointSpec>(
         &self,
-        endpoint: String,
-        data: HashMap<&str, String>,
+        request: &E::Request,
+    ) -> Result<E::Response, Error> {
+        let mut body = serde_json::to_value(request)?;
+        if let (Some(tipo), Value::Object(fields)) = (E::ENDPOINT.tipo(), &mut body) {
+            fields.insert("tipo".to_string(), Value::from(tipo));
+        }
+        #[cfg(feature = "cache")]
+        if let (Some(cache), Some(plan)) = (&self.cache, self.plan()) {
+            if E::ENDPOINT.is_allowed_for(plan) {
+                let url = self.get_url(E::ENDPOINT)?;
+                if let Some(cached) = cache.get(E::ENDPOINT, &url, &body) {
+                    if let Ok(response) = serde_json::from_value(cached) {
+                        tracing::debug!(endpoint = E::ENDPOINT.name(), "answered from cache");
+                        return Ok(response);
+                    }
+                }
+                let response: E::Response = self.query(E::ENDPOINT, body.clone()).await?;
+                cache.put(E::ENDPOINT, &url, &body, serde_json::to_value(&response)?);
+                return Ok(response);
+            }
+        }
+        self.query(E::ENDPOINT, body).await
+    }
+
+    /// Checks the plan, budget and rate limit of `endpoint` before sending the query
+    async fn query<T: DeserializeOwned + Serialize>(
+        &self,
+        endpoint: Endpoint,
+        body: Value,
     ) -> Result<T, Error> {
-        if self.token_str.is_none() {
+        let url = self.get_url(endpoint)?;
+        if self.token().is_none() {
             return Err(Error::NoLoginToken);
         }
+        if let Some(budget) = &self.query_budget {
+            budget.try_consume(endpoint)?;
+        }
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+        self.send_with_retries(Some(endpoint), &url, &body).await
+    }
+
+    /// Sends an HTTP request, returns JSON. Transient failures are retried
+    /// when a retry policy is set.
+    pub async fn request<T: DeserializeOwned + Serialize>(
+        &self,
+        endpoint: String,
+        body: Value,
+    ) -> Result<T, Error> {
+        self.send_with_retries(None, &endpoint, &body).await
+    }
+
+    /// `request` inside a `sigma.request` span, `endpoint` names the query in
+    /// the span and in schema drift warnings
+    async fn send_with_retries<T: DeserializeOwned + Serialize>(
+        &self,
+        endpoint: Option<Endpoint>,
+        url: &str,
+        body: &Value,
+    ) -> Result<T, Error> {
+        let span = tracing::info_span!(
+            "sigma.request",
+            endpoint = endpoint.map_or("custom", |endpoint| endpoint.name()),
+            plan = self.plan().map_or("none", |plan| plan.as_str()),
+            url,
+            sigma_key = %self.sensitive(&self.token().unwrap_or_default()),
+            query = %self.sensitive(body),
+            status = Empty,
+            latency_ms = Empty,
+            retries = 0,
+        );
+        let start = Instant::now();
+        let result = self
+            .retry_loop(endpoint, url, body)
+            .instrument(span.clone())
+            .await;
+        span.record("latency_ms", start.elapsed().as_millis() as u64);
+        if let Err(error) = &result {
+            tracing::warn!(
+                parent: &span,
+                status = ?error.status(),
+                error = %self.sensitive(error),
+                "sigma query failed"
+            );
+        }
+        result
+    }
+
+    async fn retry_loop<T: DeserializeOwned + Serialize>(
+        &self,
+        endpoint: Option<Endpoint>,
+        url: &str,
+        body: &Value,
+    ) -> Result<T, Error> {
+        let mut attempt = 1;
+        let mut relogged = false;
+        loop {
+            let token = self.token().ok_or(Error::NoLoginToken)?;
+            let error = match self.send_request(endpoint, url, body, token.clone()).await {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+            if error.is_auth_expired() && !relogged && self.relogin.provider.is_some() {
+                relogged = true;
+                tracing::info!("token rejected, logging in again");
+                if let Err(relogin) = self.refresh_token(&token).await {
+                    tracing::warn!(error = %self.sensitive(&relogin), "could not log in again");
+                    return Err(match error {
+                        Error::Unauthorized { status, message } => Error::Unauthorized {
+                            status,
+                            message: format!("{} (logging in again failed: {})", message, relogin),
+                        },
+                        error => error,
+                    });
+                }
+                continue;
+            }
+            attempt += 1;
+            let policy = match &self.retry_policy {
+                Some(policy) => policy,
+                None => return Err(error),
+            };
+            let delay = match policy.delay_for(attempt, &error) {
+                Some(delay) => delay,
+                None => return Err(error),
+            };
+            Span::current().record("retries", attempt - 1);
+            tracing::debug!(
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                status = ?error.status(),
+                "retrying sigma query"
+            );
+            policy.notify(&RetryAttempt {
+                attempt,
+                delay,
+                error: &error,
+            });
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn send_request<T: DeserializeOwned + Serialize>(
+        &self,
+        endpoint: Option<Endpoint>,
+        url: &str,
+        body: &Value,
+        token: String,
+    ) -> Result<T, Error> {
         let response = self
-            .http_client
-            .post(endpoint)
-            .header("sigma-key", self.token_str.as_ref().unwrap())
-            .json(&data)
-            .send()
+            .post(url, vec![("sigma-key".to_string(), token)], body)
             .await?;
-        if response.status().is_success() {
-            let rjson: T = response.json().await?;
-            Ok(rjson)
-        } else {
-            let rjson_error: ErrorResponse = response.json().await?;
-            Err(Error::ApiError(rjson_error))
+        Span::current().record("status", response.status);
+        read_response(response, &self.decoder, endpoint)
+    }
+
+    /// Logs in with the credentials provider, unless a clone of the client
+    /// already replaced the `expired` token. Queries rejected at the same
+    /// time wait for the first login and then find the new token.
+    async fn refresh_token(&self, expired: &str) -> Result<(), Error> {
+        let _login = self.relogin.lock.lock().await;
+        if self.token().as_deref() == Some(expired) {
+            let provider = match &self.relogin.provider {
+                Some(provider) => provider,
+                None => return Ok(()),
+            };
+            let credentials = provider.credentials().await?;
+            self.login_with_credentials(credentials.username, credentials.password)
+                .await?;
+            if let Some(hook) = &self.relogin.on_token_refreshed {
+                hook(&self.session());
+            }
         }
+        Ok(())
+    }
+
+    async fn post<B: Serialize>(
+        &self,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: &B,
+    ) -> Result<TransportResponse, Error> {
+        let request = TransportRequest {
+            method: Method::Post,
+            url: url.to_string(),
+            headers,
+            body: Some(serde_json::to_value(body)?),
+        };
+        self.transport.send(request).await
     }
 
     /// Login with username and password, retrive token and plan
     pub async fn login_with_credentials(
-        &mut self,
+        &self,
         username: String,
         password: String,
     ) -> Result<(), Error> {
         let endpoint = format!("{}/api/sigma/client/login", self.base_url);
+        let span = tracing::info_span!(
+            "sigma.login",
+            url = %endpoint,
+            username = %self.sensitive(&username),
+            password = %self.sensitive(&password),
+            status = Empty,
+            plan = Empty,
+            latency_ms = Empty,
+        );
+        let start = Instant::now();
         let data = HashMap::from([("username", username), ("password", password)]);
-        let response = self.http_client.post(endpoint).json(&data).send().await?;
-        if response.status().is_success() {
-            let rjson: LoginResponse = response.json().await?;
-            self.token_str = Some(rjson.token);
-            self.plan_id = Some(usize::from(rjson.plan));
+        let result: Result<(), Error> = async {
+            let response = self.post(&endpoint, Vec::new(), &data).await?;
+            Span::current().record("status", response.status);
+            let rjson: LoginResponse = read_response(response, &self.decoder, None)?;
+            let plan = Plan::try_from(rjson.plan)?;
+            Span::current().record("plan", plan.as_str());
+            self.set_api_token(&rjson.token, plan);
             Ok(())
-        } else {
-            let rjson_error: ErrorResponse = response.json().await?;
-            Err(Error::ApiError(rjson_error))
         }
+        .instrument(span.clone())
+        .await;
+        span.record("latency_ms", start.elapsed().as_millis() as u64);
+        if let Err(error) = &result {
+            tracing::warn!(
+                parent: &span,
+                status = ?error.status(),
+                error = %self.sensitive(error),
+                "sigma login failed"
+            );
+        }
+        result
     }
+}
 
-    pub async fn get_data_from_dni(&mut self, dni: String) -> Result<DNIStandardResponse, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/dni", None);
-        let data = HashMap::from([("dni", dni)]);
-        let result: DNIStandardResponse = self.request(endpoint, data).await?;
-        Ok(result)
-    }
-
-    pub async fn get_phones_from_dni(&mut self, dni: String) -> Result<Vec<PhoneNumber>, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/dni_celular", None);
-        let data = HashMap::from([("dni", dni)]);
-        let result: Vec<PhoneNumber> = self.request(endpoint, data).await?;
-        Ok(result)
-    }
-
-    pub async fn get_plate(&mut self, plate: String) -> Result<Vec<PlateHistory>, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/patente", None);
-        let data = HashMap::from([("patente", plate)]);
-        let result: Vec<PlateHistory> = self.request(endpoint, data).await?;
-        Ok(result)
-    }
-
-    pub async fn get_plate_from_dni(&mut self, dni: String) -> Result<Vec<PlateHistory>, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/patente_dni", None);
-        let data = HashMap::from([("dni", dni)]);
-        let result: Vec<PlateHistory> = self.request(endpoint, data).await?;
-        Ok(result)
-    }
-
-    pub async fn get_query_data_breach(
-        &mut self,
-        query: String,
-    ) -> Result<Vec<BreachCredentials>, Error> {
-        let endpoint = self.get_url("/osint/argentina/search_engine/data_breach", None);
-        let data = HashMap::from([("query", query)]);
-        let result: Vec<BreachCredentials> = self.request(endpoint, data).await?;
-        Ok(result)
-    }
-
-    pub async fn get_data_from_dni_profesional(
-        &mut self,
-        dni: String,
-        gender: String,
-    ) -> Result<DNIProfesional, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/dni_two", None);
-        let data = HashMap::from([("dato", format!("{}:{}", dni, gender))]);
-        let response: DNIProfesional = self.request(endpoint, data).await?;
-        Ok(response)
-    }
-
-    pub async fn get_names(
-        &mut self,
-        name: String,
-        parameters: Option<HashMap<&str, String>>,
-    ) -> Result<Vec<PersonaNombre>, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/nombre", None);
-        let mut data = HashMap::from([("nombre", name)]);
-        if !parameters.is_none() {
-            data.extend(parameters.unwrap());
-        }
-        let response: Vec<PersonaNombre> = self.request(endpoint, data).await?;
-        Ok(response)
-    }
-
-    pub async fn get_movistar_email(&mut self, phone: String) -> Result<MovistarEmail, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/movistar", None);
-        let data = HashMap::from([("num", phone)]);
-        let response: MovistarEmail = self.request(endpoint, data).await?;
-        Ok(response)
-    }
-
-    pub async fn get_people_by_address(
-        &mut self,
-        address: String,
-    ) -> Result<Vec<PersonaDireccion>, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/direccion", None);
-        let data = HashMap::from([("direccion", address)]);
-        let response: Vec<PersonaDireccion> = self.request(endpoint, data).await?;
-        Ok(response)
-    }
-
-    pub async fn get_data_by_number(
-        &mut self,
-        number: String,
-    ) -> Result<Vec<PersonaFromNumero>, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/celular", None);
-        let data = HashMap::from([("num", number)]);
-        let response: Vec<PersonaFromNumero> = self.request(endpoint, data).await?;
-        Ok(response)
-    }
-
-    pub async fn get_data_by_number_magic(
-        &mut self,
-        number: String,
-    ) -> Result<PersonaFromNumeroMagic, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/magic", None);
-        let data = HashMap::from([("dato", number), ("tipo", "buscar_celular".to_string())]);
-        let response: PersonaFromNumeroMagic = self.request(endpoint, data).await?;
-        Ok(response)
-    }
-
-    pub async fn get_data_by_cvu(&mut self, cvu_alias: String) -> Result<TitularCBU, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/magic", None);
-        let data = HashMap::from([
-            ("dato", cvu_alias),
-            ("tipo", "buscar_cbu_alias".to_string()),
-        ]);
-        let response: TitularCBU = self.request(endpoint, data).await?;
-        Ok(response)
-    }
-
-    pub async fn get_data_by_email(&mut self, email: String) -> Result<EmailResultados, Error> {
-        let endpoint = self.get_url("/osint/argentina/resolver/magic", None);
-        let data = HashMap::from([("dato", email), ("tipo", "buscar_email".to_string())]);
-        let response: EmailResultados = self.request(endpoint, data).await?;
-        Ok(response)
+/// Deserializes a successful response or maps an error status to an `Error`
+fn read_response<T: DeserializeOwned + Serialize>(
+    response: TransportResponse,
+    decoder: &Decoder,
+    endpoint: Option<Endpoint>,
+) -> Result<T, Error> {
+    if (200..300).contains(&response.status) {
+        decoder.decode(&response.body, endpoint)
+    } else {
+        Err(Error::from_response(
+            response.status,
+            response.header("retry-after"),
+            &response.body,
+        ))
     }
 }
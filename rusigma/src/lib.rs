@@ -1,7 +1,33 @@
+pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod builder;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod cbu;
 pub mod client;
+pub mod credentials;
+pub mod decode;
+pub mod document;
+pub mod endpoints;
 pub mod errors;
+pub mod filters;
 pub mod http;
+pub mod limits;
+pub mod parsed;
+pub mod phone;
+pub mod plan;
+pub mod plate;
+pub mod request;
 pub mod response;
+pub mod retry;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transport;
 
 pub type SigmaClient = client::SigmaClient;
 pub type Error = errors::Error;
+pub type SigmaClientBuilder = builder::SigmaClientBuilder;
+pub type Plan = plan::Plan;
+
+pub use api::SigmaApi;
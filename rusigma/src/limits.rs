@@ -0,0 +1,234 @@
+use crate::endpoints::Endpoint;
+use crate::errors::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Token bucket limiting how fast queries are sent
+///
+/// The bucket starts full with `capacity` tokens and gains one token every
+/// `refill_every`. A query takes a token, waiting for one when the bucket is empty.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_every: Duration,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, refill_every: Duration) -> RateLimiter {
+        let capacity = f64::from(capacity.max(1));
+        RateLimiter {
+            capacity,
+            refill_every,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Allows bursts of `per_minute` queries and `per_minute` queries per minute on average
+    pub fn per_minute(per_minute: u32) -> RateLimiter {
+        RateLimiter::new(per_minute, Duration::from_secs(60) / per_minute.max(1))
+    }
+
+    /// Waits until a token is available and takes it
+    pub async fn acquire(&self) {
+        while let Some(wait) = self.try_acquire() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes a token if one is available, otherwise returns how long to wait for it
+    pub fn try_acquire(&self) -> Option<Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let refilled = now.duration_since(bucket.updated).as_secs_f64()
+            / self.refill_every.as_secs_f64().max(f64::EPSILON);
+        bucket.tokens = (bucket.tokens + refilled).min(self.capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(self.refill_every.mul_f64(1.0 - bucket.tokens))
+        }
+    }
+}
+
+/// Daily number of queries allowed per endpoint, counted locally
+///
+/// Usage is counted per calendar day in Argentina (UTC-3) unless another
+/// offset is set. Counters are kept in memory, or in a JSON file with
+/// `persist_to` so they survive between runs.
+#[derive(Debug)]
+pub struct QueryBudget {
+    limits: HashMap<Endpoint, u32>,
+    default_limit: Option<u32>,
+    utc_offset: i64,
+    path: Option<PathBuf>,
+    usage: Mutex<DailyUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+struct DailyUsage {
+    day: String,
+    queries: HashMap<String, u32>,
+}
+
+/// Usage of one endpoint for the current day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointUsage {
+    pub endpoint: Endpoint,
+    pub used: u32,
+    pub limit: Option<u32>,
+}
+
+impl Default for QueryBudget {
+    fn default() -> Self {
+        QueryBudget::new()
+    }
+}
+
+impl QueryBudget {
+    pub fn new() -> QueryBudget {
+        QueryBudget {
+            limits: HashMap::new(),
+            default_limit: None,
+            utc_offset: -3 * 3600,
+            path: None,
+            usage: Mutex::new(DailyUsage::default()),
+        }
+    }
+
+    /// Maximum number of queries per day to `endpoint`
+    pub fn limit(mut self, endpoint: Endpoint, per_day: u32) -> Self {
+        self.limits.insert(endpoint, per_day);
+        self
+    }
+
+    /// Maximum number of queries per day to endpoints without their own limit
+    pub fn default_limit(mut self, per_day: u32) -> Self {
+        self.default_limit = Some(per_day);
+        self
+    }
+
+    /// Offset from UTC, in hours, of the time zone that decides when a day starts
+    pub fn utc_offset_hours(mut self, hours: i64) -> Self {
+        self.utc_offset = hours * 3600;
+        self
+    }
+
+    /// Loads the counters from `path`, if it exists, and saves them there after every query
+    pub fn persist_to<P: AsRef<Path>>(mut self, path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            let content = fs::read_to_string(&path).map_err(|e| storage_error(&path, e))?;
+            let usage: DailyUsage =
+                serde_json::from_str(&content).map_err(|e| storage_error(&path, e))?;
+            *self.usage.get_mut().unwrap() = usage;
+        }
+        self.path = Some(path);
+        Ok(self)
+    }
+
+    pub fn limit_for(&self, endpoint: Endpoint) -> Option<u32> {
+        self.limits.get(&endpoint).copied().or(self.default_limit)
+    }
+
+    /// Queries made today to `endpoint`
+    pub fn used(&self, endpoint: Endpoint) -> u32 {
+        let mut usage = self.usage.lock().unwrap();
+        self.roll_over(&mut usage);
+        usage.queries.get(endpoint.name()).copied().unwrap_or(0)
+    }
+
+    /// Usage and limit of every endpoint for the current day
+    pub fn report(&self) -> Vec<EndpointUsage> {
+        Endpoint::ALL
+            .iter()
+            .map(|&endpoint| EndpointUsage {
+                endpoint,
+                used: self.used(endpoint),
+                limit: self.limit_for(endpoint),
+            })
+            .collect()
+    }
+
+    /// Counts one query to `endpoint`, failing without counting it when the
+    /// daily limit has been reached
+    pub fn try_consume(&self, endpoint: Endpoint) -> Result<(), Error> {
+        let mut usage = self.usage.lock().unwrap();
+        let mut next = usage.clone();
+        self.roll_over(&mut next);
+        let used = next.queries.entry(endpoint.name().to_string()).or_insert(0);
+        if let Some(limit) = self.limit_for(endpoint) {
+            if *used >= limit {
+                return Err(Error::BudgetExceeded { endpoint, limit });
+            }
+        }
+        *used += 1;
+        // Counted only once saved, a query refused by a failed save is not counted
+        self.save(&next)?;
+        *usage = next;
+        Ok(())
+    }
+
+    fn roll_over(&self, usage: &mut DailyUsage) {
+        let today = civil_date(self.utc_offset);
+        if usage.day != today {
+            usage.day = today;
+            usage.queries.clear();
+        }
+    }
+
+    fn save(&self, usage: &DailyUsage) -> Result<(), Error> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let content = serde_json::to_string(usage)?;
+        // Written aside and renamed, so an interrupted write never truncates the counters
+        let temporary = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&temporary, content)
+            .and_then(|_| fs::rename(&temporary, path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temporary);
+                storage_error(path, e)
+            })
+    }
+}
+
+fn storage_error<E: std::fmt::Display>(path: &Path, e: E) -> Error {
+    Error::BudgetStorage(format!("{}: {}", path.display(), e))
+}
+
+/// Current date as `YYYY-MM-DD` in the time zone `utc_offset` seconds from UTC
+fn civil_date(utc_offset: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let days = (now + utc_offset).div_euclid(86400);
+    // Days since 1970-01-01 to a proleptic Gregorian date (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
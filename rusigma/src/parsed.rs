@@ -0,0 +1,147 @@
+use crate::document::Gender;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Date type of the parsed date fields
+pub use chrono::NaiveDate;
+
+/// Date layouts seen in Sigma responses
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%Y/%m/%d"];
+
+/// Types read from the free-form text of a response field
+pub trait FromRaw: Sized {
+    fn from_raw(raw: &str) -> Option<Self>;
+}
+
+/// Accepts the date layouts Sigma uses, ignoring a trailing time
+impl FromRaw for NaiveDate {
+    fn from_raw(raw: &str) -> Option<Self> {
+        let date = raw.trim().split(['T', ' ']).next()?;
+        DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+    }
+}
+
+/// Accepts `SI`/`NO` in any case, as well as `true`/`false` and `1`/`0`
+impl FromRaw for bool {
+    fn from_raw(raw: &str) -> Option<Self> {
+        match raw.trim().to_uppercase().as_str() {
+            "SI" | "SÍ" | "S" | "TRUE" | "1" => Some(true),
+            "NO" | "N" | "FALSE" | "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl FromRaw for Gender {
+    fn from_raw(raw: &str) -> Option<Self> {
+        raw.parse().ok()
+    }
+}
+
+/// A response field parsed into `T`, keeping the text Sigma sent
+///
+/// `value` is `None` when the text could not be parsed. Serializes back to
+/// the original text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed<T> {
+    raw: String,
+    value: Option<T>,
+}
+
+impl<T: FromRaw> Parsed<T> {
+    pub fn new(raw: impl Into<String>) -> Self {
+        let raw = raw.into();
+        let value = T::from_raw(&raw);
+        Parsed { raw, value }
+    }
+}
+
+impl<T> Parsed<T> {
+    /// Text of the field as sent by Sigma
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn value(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    pub fn is_parsed(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
+impl<T: Copy> Parsed<T> {
+    pub fn get(&self) -> Option<T> {
+        self.value
+    }
+}
+
+impl<T> fmt::Display for Parsed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl<T> Serialize for Parsed<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+/// Reads strings, and numbers or booleans as their text
+impl<'de, T: FromRaw> Deserialize<'de> for Parsed<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(RawVisitor(PhantomData))
+    }
+}
+
+struct RawVisitor<T>(PhantomData<T>);
+
+impl<'de, T: FromRaw> Visitor<'de> for RawVisitor<T> {
+    type Value = Parsed<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string, number or boolean")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Parsed::new(v))
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Parsed::new(v.to_string()))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Parsed::new(v.to_string()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Parsed::new(v.to_string()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Parsed::new(v.to_string()))
+    }
+}
+
+/// Described as the string it serializes to
+#[cfg(feature = "schema")]
+impl<T> schemars::JsonSchema for Parsed<T> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        String::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::errors::Error;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Digits of a national number: area code plus subscriber number
+const NATIONAL_LENGTH: usize = 10;
+
+/// Area codes with 3 digits; Buenos Aires uses `11` and every other area a 4 digit code
+const AREA_CODES_3: &[&str] = &[
+    "220", "221", "223", "230", "236", "237", "249", "260", "261", "263", "264", "266", "280",
+    "291", "294", "297", "298", "299", "336", "341", "342", "343", "345", "348", "351", "353",
+    "358", "362", "364", "370", "376", "379", "380", "381", "383", "385", "387", "388",
+];
+
+/// Argentine phone number in national format: 10 digits, area code without
+/// the leading `0` followed by the subscriber number without the `15`
+///
+/// Parses the usual ways of writing a number, e.g. `+54 9 11 1234-5678`,
+/// `011 15 1234 5678` or `1112345678`, all read as `1112345678`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhoneNumber {
+    digits: String,
+    area_code_len: usize,
+}
+
+impl PhoneNumber {
+    /// The 10 digits sent to Sigma
+    pub fn as_str(&self) -> &str {
+        &self.digits
+    }
+
+    pub fn area_code(&self) -> &str {
+        &self.digits[..self.area_code_len]
+    }
+
+    pub fn subscriber_number(&self) -> &str {
+        &self.digits[self.area_code_len..]
+    }
+
+    /// International format of a mobile line, `+549` followed by the national number
+    pub fn international_mobile(&self) -> String {
+        format!("+549{}", self.digits)
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.digits)
+    }
+}
+
+impl FromStr for PhoneNumber {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let international = trimmed.starts_with('+');
+        let mut digits = String::new();
+        for c in trimmed.trim_start_matches('+').chars() {
+            match c {
+                '0'..='9' => digits.push(c),
+                ' ' | '-' | '.' | '(' | ')' => {}
+                _ => {
+                    return Err(Error::InvalidInput(format!(
+                        "phone number {} contains {:?}",
+                        s, c
+                    )))
+                }
+            }
+        }
+
+        // Without a `+` or `00`, a country code is recognized by its length:
+        // no area code starts with 5, so a national number never begins with 54
+        let mut national = digits.as_str();
+        let mut with_country_code =
+            international || (national.starts_with("54") && national.len() >= 12);
+        if let Some(rest) = national.strip_prefix("00") {
+            national = rest;
+            with_country_code = true;
+        }
+        if with_country_code {
+            national = national.strip_prefix("54").ok_or_else(|| {
+                Error::InvalidInput(format!("phone number {} is not from Argentina", s))
+            })?;
+            national = national.strip_prefix('9').unwrap_or(national);
+        }
+        national = national.strip_prefix('0').unwrap_or(national);
+
+        let area_code_len = area_code_len(national);
+        let national = if national.len() == NATIONAL_LENGTH + 2
+            && &national[area_code_len..area_code_len + 2] == "15"
+        {
+            format!(
+                "{}{}",
+                &national[..area_code_len],
+                &national[area_code_len + 2..]
+            )
+        } else {
+            national.to_string()
+        };
+
+        if national.len() != NATIONAL_LENGTH {
+            return Err(Error::InvalidInput(format!(
+                "phone number {} must have 10 digits with the area code, it has {}",
+                s,
+                national.len()
+            )));
+        }
+        // Area codes start with 2 or 3, except Buenos Aires, the only one starting with 1
+        let area = national.as_bytes();
+        if !(matches!(area[0], b'2' | b'3') || area[..2] == *b"11") {
+            return Err(Error::InvalidInput(format!(
+                "phone number {} has an invalid area code",
+                s
+            )));
+        }
+        Ok(PhoneNumber {
+            digits: national,
+            area_code_len,
+        })
+    }
+}
+
+impl Serialize for PhoneNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.digits)
+    }
+}
+
+fn area_code_len(national: &str) -> usize {
+    if national.starts_with("11") {
+        2
+    } else if national.len() >= 3 && AREA_CODES_3.contains(&&national[..3]) {
+        3
+    } else {
+        4
+    }
+}
@@ -0,0 +1,86 @@
+use crate::errors::Error;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Subscription plan of a Sigma account
+///
+/// Sigma identifies plans by a numeric ID (returned by the login endpoint)
+/// and by a name used as a path segment in every query URL.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Plan {
+    Free,
+    Profesional,
+    Medium,
+    Standard,
+    Comunidades,
+}
+
+impl Plan {
+    pub const ALL: [Plan; 5] = [
+        Plan::Free,
+        Plan::Profesional,
+        Plan::Medium,
+        Plan::Standard,
+        Plan::Comunidades,
+    ];
+
+    /// Returns the plan for the numeric ID used by the Sigma API
+    pub fn from_id(id: u8) -> Option<Plan> {
+        Plan::ALL.get(usize::from(id)).copied()
+    }
+
+    /// Numeric ID used by the Sigma API
+    pub fn id(self) -> u8 {
+        match self {
+            Plan::Free => 0,
+            Plan::Profesional => 1,
+            Plan::Medium => 2,
+            Plan::Standard => 3,
+            Plan::Comunidades => 4,
+        }
+    }
+
+    /// Name of the plan as it appears in query URLs
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Plan::Free => "free",
+            Plan::Profesional => "profesional",
+            Plan::Medium => "medium",
+            Plan::Standard => "standard",
+            Plan::Comunidades => "comunidades",
+        }
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<u8> for Plan {
+    type Error = Error;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        Plan::from_id(id).ok_or(Error::UnknownPlan(id.to_string()))
+    }
+}
+
+/// Parses either the plan name (`medium`) or its numeric ID (`2`)
+impl FromStr for Plan {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(id) = s.parse::<u8>() {
+            return Plan::try_from(id);
+        }
+        Plan::ALL
+            .iter()
+            .find(|plan| plan.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::UnknownPlan(s.to_string()))
+    }
+}
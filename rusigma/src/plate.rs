@@ -0,0 +1,108 @@
+use crate::errors::Error;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Layout of an Argentine vehicle plate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlateFormat {
+    /// Cars 1995-2016, `ABC123`
+    Legacy,
+    /// Cars since 2016, `AB123CD`
+    Mercosur,
+    /// Motorcycles 1995-2016, `123ABC`
+    LegacyMotorcycle,
+    /// Motorcycles since 2016, `A123BCD`
+    MercosurMotorcycle,
+}
+
+impl PlateFormat {
+    const ALL: [PlateFormat; 4] = [
+        PlateFormat::Legacy,
+        PlateFormat::Mercosur,
+        PlateFormat::LegacyMotorcycle,
+        PlateFormat::MercosurMotorcycle,
+    ];
+
+    /// `L` for a letter and `D` for a digit
+    fn pattern(self) -> &'static str {
+        match self {
+            PlateFormat::Legacy => "LLLDDD",
+            PlateFormat::Mercosur => "LLDDDLL",
+            PlateFormat::LegacyMotorcycle => "DDDLLL",
+            PlateFormat::MercosurMotorcycle => "LDDDLLL",
+        }
+    }
+
+    fn matches(self, plate: &str) -> bool {
+        let pattern = self.pattern();
+        plate.len() == pattern.len()
+            && plate
+                .chars()
+                .zip(pattern.chars())
+                .all(|(c, kind)| match kind {
+                    'L' => c.is_ascii_uppercase(),
+                    _ => c.is_ascii_digit(),
+                })
+    }
+}
+
+/// Argentine vehicle plate, upper case without spaces or dashes
+///
+/// Parses `abc 123`, `AB-123-CD` and the motorcycle formats.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Plate {
+    value: String,
+    format: PlateFormat,
+}
+
+impl Plate {
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    pub fn format(&self) -> PlateFormat {
+        self.format
+    }
+
+    pub fn is_motorcycle(&self) -> bool {
+        matches!(
+            self.format,
+            PlateFormat::LegacyMotorcycle | PlateFormat::MercosurMotorcycle
+        )
+    }
+}
+
+impl fmt::Display for Plate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+impl FromStr for Plate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value: String = s
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '.'))
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        PlateFormat::ALL
+            .iter()
+            .find(|format| format.matches(&value))
+            .map(|&format| Plate { value, format })
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "plate {} must look like ABC123, AB123CD, 123ABC or A123BCD",
+                    s
+                ))
+            })
+    }
+}
+
+impl Serialize for Plate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.value)
+    }
+}
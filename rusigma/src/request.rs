@@ -0,0 +1,46 @@
+use crate::document::Dni;
+use crate::filters::NameSearchFilters;
+use crate::phone::PhoneNumber;
+use crate::plate::Plate;
+use serde::Serialize;
+
+/// Body of the queries taking a DNI
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DniRequest {
+    pub dni: Dni,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlateRequest {
+    pub patente: Plate,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LeaksRequest {
+    pub query: String,
+}
+
+/// Body of the queries taking a generic `dato`, the `tipo` of magic queries
+/// is added from the endpoint declaration
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DatoRequest {
+    pub dato: String,
+}
+
+/// Name search, the filters are sent as extra top level keys
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct NameRequest {
+    pub nombre: String,
+    #[serde(flatten)]
+    pub filtros: NameSearchFilters,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PhoneRequest {
+    pub num: PhoneNumber,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AddressRequest {
+    pub direccion: String,
+}
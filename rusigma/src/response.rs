@@ -1,21 +1,74 @@
+//! Bodies returned by Sigma
+//!
+//! Every type keeps the fields it does not know about in `extra`. They are
+//! not serialized again, so the serialized form keeps a stable schema.
+
 extern crate reqwest;
 
-use serde::Deserialize;
+use crate::document::Gender;
+use crate::parsed::Parsed;
+use crate::plate::Plate;
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// JSON Schema of every response type, keyed by type name. Serialized field
+/// names are the ones used by Sigma and do not change between versions.
+#[cfg(feature = "schema")]
+pub fn schemas() -> std::collections::BTreeMap<&'static str, schemars::schema::RootSchema> {
+    std::collections::BTreeMap::from([
+        ("ErrorResponse", schemars::schema_for!(ErrorResponse)),
+        ("LoginResponse", schemars::schema_for!(LoginResponse)),
+        (
+            "DNIStandardResponse",
+            schemars::schema_for!(DNIStandardResponse),
+        ),
+        ("PhoneRecord", schemars::schema_for!(PhoneRecord)),
+        ("PlateHistory", schemars::schema_for!(PlateHistory)),
+        (
+            "BreachCredentials",
+            schemars::schema_for!(BreachCredentials),
+        ),
+        ("ObraSocial", schemars::schema_for!(ObraSocial)),
+        ("DNIProfesional", schemars::schema_for!(DNIProfesional)),
+        ("PersonaNombre", schemars::schema_for!(PersonaNombre)),
+        ("MovistarEmail", schemars::schema_for!(MovistarEmail)),
+        ("PersonaDireccion", schemars::schema_for!(PersonaDireccion)),
+        (
+            "PersonaFromNumero",
+            schemars::schema_for!(PersonaFromNumero),
+        ),
+        (
+            "PersonaFromNumeroMagic",
+            schemars::schema_for!(PersonaFromNumeroMagic),
+        ),
+        ("EmailResultados", schemars::schema_for!(EmailResultados)),
+        ("TitularCBU", schemars::schema_for!(TitularCBU)),
+    ])
+}
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ErrorResponse {
     pub error: bool,
     #[serde(rename = "mensaje")]
     pub message: String,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LoginResponse {
     pub token: String,
     pub plan: u8,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DNIStandardResponse {
     #[serde(rename = "doc")]
     pub documento: String,
@@ -28,21 +81,27 @@ pub struct DNIStandardResponse {
     pub localidad: String,
     pub provincia: String,
     pub codigo_postal: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct PhoneNumber {
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PhoneRecord {
     #[serde(rename = "doc")]
-    pub documento: String,
-    pub numero: String,
-    pub nombre: String,
-    pub localidad: String,
-    pub provincia: String,
-    pub codigo_postal: String,
-    pub empresa: String,
+    pub documento: Option<String>,
+    pub numero: Option<String>,
+    pub nombre: Option<String>,
+    pub localidad: Option<String>,
+    pub provincia: Option<String>,
+    pub codigo_postal: Option<String>,
+    pub empresa: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PlateHistory {
     pub patente: Option<String>,
     pub documento: Option<String>,
@@ -58,27 +117,44 @@ pub struct PlateHistory {
     pub codigo_postal: Option<String>,
     pub localidad: Option<String>,
     pub transferencia: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+impl PlateHistory {
+    /// The plate of the record, `None` when it is missing or not a valid plate
+    pub fn plate(&self) -> Option<Plate> {
+        self.patente.as_deref()?.parse().ok()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BreachCredentials {
     #[serde(rename = "usuario")]
     pub username: String,
     pub password: String,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ObraSocial {
     pub cobertura: Option<String>,
     pub nombre: Option<String>,
     #[serde(rename = "dni")]
     pub documento: Option<String>,
-    pub sexo: Option<String>,
+    pub sexo: Option<Parsed<Gender>>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DNIProfesional {
-    pub emision: Option<String>,
+    /// Issue date of the DNI
+    pub emision: Option<Parsed<NaiveDate>>,
     pub apellido: Option<String>,
     pub nombres: Option<String>,
     pub cuil: Option<String>,
@@ -95,28 +171,58 @@ pub struct DNIProfesional {
     pub foto: Option<String>,
     pub tramite: Option<String>,
     pub documento: Option<String>,
-    pub fallecido: Option<String>,
+    pub fallecido: Option<Parsed<bool>>,
     pub codigo_postal: Option<String>,
     pub cobertura: Option<Vec<ObraSocial>>,
-    pub fecha_nacimiento: Option<String>,
+    pub fecha_nacimiento: Option<Parsed<NaiveDate>>,
     pub edad: Option<u8>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+impl DNIProfesional {
+    /// Age sent by Sigma, or computed from the birth date when it is missing
+    pub fn age(&self) -> Option<u8> {
+        self.edad.or_else(|| self.age_on(Local::now().date_naive()))
+    }
+
+    /// Age on `date` according to the birth date
+    pub fn age_on(&self, date: NaiveDate) -> Option<u8> {
+        let birth = self.fecha_nacimiento.as_ref()?.get()?;
+        let mut years = date.year() - birth.year();
+        if (date.month(), date.day()) < (birth.month(), birth.day()) {
+            years -= 1;
+        }
+        u8::try_from(years).ok()
+    }
+
+    pub fn is_deceased(&self) -> Option<bool> {
+        self.fallecido.as_ref()?.get()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PersonaNombre {
-    pub nombre: String,
-    pub documento: String,
-    pub provincia: String,
+    pub nombre: Option<String>,
+    pub documento: Option<String>,
+    pub provincia: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MovistarEmail {
     #[serde(rename = "num")]
     pub numero: String,
     pub email: String,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PersonaDireccion {
     pub numero: Option<String>,
     #[serde(rename = "doc")]
@@ -127,40 +233,54 @@ pub struct PersonaDireccion {
     pub provincia: Option<String>,
     pub codigo_postal: Option<String>,
     pub empresa: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PersonaFromNumero {
-    pub celular: String,
-    pub documento: String,
-    pub nombre: String,
-    pub direccion: String,
-    pub localidad: String,
-    pub provincia: String,
-    pub codigo_postal: String,
-    pub empresa: String,
+    pub celular: Option<String>,
+    pub documento: Option<String>,
+    pub nombre: Option<String>,
+    pub direccion: Option<String>,
+    pub localidad: Option<String>,
+    pub provincia: Option<String>,
+    pub codigo_postal: Option<String>,
+    pub empresa: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PersonaFromNumeroMagic {
     pub nombre: String,
     pub apellido: String,
     pub email: String,
     pub numero: String,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct EmailResultados {
-    pub nombre: String,
-    pub apellido: String,
-    pub email: String,
+    pub nombre: Option<String>,
+    pub apellido: Option<String>,
+    pub email: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TitularCBU {
-    pub nombre: String,
-    pub cuit: String,
-    pub banco: String,
-    pub cbu: String,
-    pub cuenta_tipo: String,
+    pub nombre: Option<String>,
+    pub cuit: Option<String>,
+    pub banco: Option<String>,
+    pub cbu: Option<String>,
+    pub cuenta_tipo: Option<String>,
+    #[serde(flatten, skip_serializing)]
+    pub extra: BTreeMap<String, Value>,
 }
@@ -0,0 +1,164 @@
+use crate::errors::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Information passed to the retry hook before every new attempt
+#[derive(Debug)]
+pub struct RetryAttempt<'a> {
+    /// Number of the attempt about to be made, the first retry is attempt 2
+    pub attempt: u32,
+    /// Time the client waits before the attempt
+    pub delay: Duration,
+    /// Error returned by the previous attempt
+    pub error: &'a Error,
+}
+
+type RetryHook = Arc<dyn Fn(&RetryAttempt) + Send + Sync>;
+
+/// Opt-in policy to retry queries that failed with a transient error
+///
+/// Only failures that cannot have been caused by the query itself are retried:
+/// timeouts, connection errors, rate limiting and 5xx responses. Every Sigma
+/// query is a read, so sending it again is safe.
+///
+/// ```
+/// # use std::time::Duration;
+/// let policy = rusigma::retry::RetryPolicy::new()
+///     .max_attempts(5)
+///     .initial_backoff(Duration::from_millis(200))
+///     .on_retry(|attempt| eprintln!("retrying: {}", attempt.error));
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: bool,
+    respect_retry_after: bool,
+    on_retry: Option<RetryHook>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new()
+    }
+}
+
+impl RetryPolicy {
+    /// 3 attempts, backoff starting at 500ms and doubling up to 30s, with jitter
+    pub fn new() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: true,
+            respect_retry_after: true,
+            on_retry: None,
+        }
+    }
+
+    /// Total number of attempts, including the first one
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Upper bound for the delay between two attempts
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Factor applied to the delay after every retry
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Randomizes every delay between half and all of its value
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Waits for the `Retry-After` delay sent with a 429 instead of the backoff.
+    /// A rate limit asking to wait longer than `max_backoff` is not retried.
+    pub fn respect_retry_after(mut self, respect: bool) -> Self {
+        self.respect_retry_after = respect;
+        self
+    }
+
+    /// Called before every retry with the attempt number, delay and last error
+    pub fn on_retry<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RetryAttempt) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    /// Delay before `attempt` (2 for the first retry) after `error`, or `None`
+    /// when the query must not be retried
+    pub fn delay_for(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        if attempt > self.max_attempts || !is_retryable(error) {
+            return None;
+        }
+        if let Error::RateLimited {
+            retry_after: Some(retry_after),
+            ..
+        } = error
+        {
+            if self.respect_retry_after {
+                return (*retry_after <= self.max_backoff).then_some(*retry_after);
+            }
+        }
+        let exponent = attempt.saturating_sub(2) as i32;
+        let backoff = self
+            .initial_backoff
+            .mul_f64(self.multiplier.powi(exponent))
+            .min(self.max_backoff);
+        if self.jitter {
+            Some(backoff.mul_f64(0.5 + fastrand::f64() / 2.0))
+        } else {
+            Some(backoff)
+        }
+    }
+
+    pub(crate) fn notify(&self, attempt: &RetryAttempt) {
+        if let Some(hook) = &self.on_retry {
+            hook(attempt);
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .field("respect_retry_after", &self.respect_retry_after)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+/// Transient failures that may succeed when the same query is sent again
+pub fn is_retryable(error: &Error) -> bool {
+    match error {
+        Error::Timeout | Error::Connection(_) | Error::RateLimited { .. } => true,
+        Error::ServerError { status, .. } => !matches!(status, 501 | 505),
+        _ => false,
+    }
+}
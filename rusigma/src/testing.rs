@@ -0,0 +1,235 @@
+//! In-memory stand-in for `SigmaClient`, enabled by the `testing` feature
+
+use crate::api::SigmaApi;
+use crate::cbu::CbuOrAlias;
+use crate::document::{Dni, Gender};
+use crate::endpoints::Endpoint;
+use crate::errors::Error;
+use crate::filters::NameSearchFilters;
+use crate::phone::PhoneNumber;
+use crate::plan::Plan;
+use crate::plate::Plate;
+use crate::response;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// A call received by a `FakeSigmaClient`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeCall {
+    pub endpoint: Endpoint,
+    /// Arguments of the call, in the order of the method parameters
+    pub args: Vec<String>,
+}
+
+/// `SigmaApi` implementation returning canned responses without any network
+///
+/// Responses are given as JSON, in the format Sigma sends them, and queued
+/// per endpoint: every call takes the next queued response or error, then
+/// falls back to the response set with `always`.
+///
+/// ```
+/// # use rusigma::endpoints::Endpoint;
+/// # use rusigma::testing::FakeSigmaClient;
+/// let fake = FakeSigmaClient::new()
+///     .respond(Endpoint::Email, serde_json::json!({
+///         "nombre": "JUAN", "apellido": "PEREZ", "email": "juan@example.com"
+///     }))
+///     .fail(Endpoint::Email, rusigma::Error::Timeout);
+/// ```
+#[derive(Debug, Default)]
+pub struct FakeSigmaClient {
+    plan: Option<Plan>,
+    queued: Mutex<HashMap<Endpoint, VecDeque<Result<Value, Error>>>>,
+    always: HashMap<Endpoint, Value>,
+    login: Mutex<VecDeque<Result<(), Error>>>,
+    calls: Mutex<Vec<FakeCall>>,
+    logins: Mutex<Vec<String>>,
+}
+
+impl FakeSigmaClient {
+    pub fn new() -> FakeSigmaClient {
+        FakeSigmaClient::default()
+    }
+
+    /// Enforces the endpoint entitlements of `plan` like the real client
+    pub fn plan(mut self, plan: Plan) -> Self {
+        self.plan = Some(plan);
+        self
+    }
+
+    /// Queues a JSON response for the next call to `endpoint`
+    pub fn respond(self, endpoint: Endpoint, body: Value) -> Self {
+        self.push(endpoint, Ok(body));
+        self
+    }
+
+    /// Queues an error for the next call to `endpoint`
+    pub fn fail(self, endpoint: Endpoint, error: Error) -> Self {
+        self.push(endpoint, Err(error));
+        self
+    }
+
+    /// Response returned by `endpoint` once its queue is empty
+    pub fn always(mut self, endpoint: Endpoint, body: Value) -> Self {
+        self.always.insert(endpoint, body);
+        self
+    }
+
+    /// Queues the result of the next login, logins succeed by default
+    pub fn login_result(self, result: Result<(), Error>) -> Self {
+        self.login.lock().unwrap().push_back(result);
+        self
+    }
+
+    pub fn push(&self, endpoint: Endpoint, result: Result<Value, Error>) {
+        self.queued
+            .lock()
+            .unwrap()
+            .entry(endpoint)
+            .or_default()
+            .push_back(result);
+    }
+
+    /// Every query received so far, in order
+    pub fn calls(&self) -> Vec<FakeCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Queries received so far for `endpoint`
+    pub fn calls_to(&self, endpoint: Endpoint) -> Vec<FakeCall> {
+        self.calls()
+            .into_iter()
+            .filter(|call| call.endpoint == endpoint)
+            .collect()
+    }
+
+    /// Usernames of every login attempt
+    pub fn logins(&self) -> Vec<String> {
+        self.logins.lock().unwrap().clone()
+    }
+
+    fn answer<T: DeserializeOwned>(&self, endpoint: Endpoint, args: &[&str]) -> Result<T, Error> {
+        self.calls.lock().unwrap().push(FakeCall {
+            endpoint,
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        });
+        if let Some(plan) = self.plan {
+            if !endpoint.is_allowed_for(plan) {
+                return Err(Error::PlanNotAllowed { endpoint, plan });
+            }
+        }
+        let queued = self
+            .queued
+            .lock()
+            .unwrap()
+            .get_mut(&endpoint)
+            .and_then(VecDeque::pop_front);
+        let body = match queued {
+            Some(result) => result?,
+            None => self.always.get(&endpoint).cloned().ok_or_else(|| {
+                Error::InvalidConfiguration(format!(
+                    "FakeSigmaClient has no response for {}",
+                    endpoint
+                ))
+            })?,
+        };
+        Ok(serde_json::from_value(body)?)
+    }
+}
+
+#[async_trait]
+impl SigmaApi for FakeSigmaClient {
+    async fn login_with_credentials(
+        &self,
+        username: String,
+        _password: String,
+    ) -> Result<(), Error> {
+        self.logins.lock().unwrap().push(username);
+        self.login.lock().unwrap().pop_front().unwrap_or(Ok(()))
+    }
+
+    async fn search_standard_dni(&self, dni: Dni) -> Result<response::DNIStandardResponse, Error> {
+        self.answer(Endpoint::StandardDni, &[dni.as_str()])
+    }
+
+    async fn search_phones_by_dni(&self, dni: Dni) -> Result<Vec<response::PhoneRecord>, Error> {
+        self.answer(Endpoint::PhonesByDni, &[dni.as_str()])
+    }
+
+    async fn search_plate(&self, plate: Plate) -> Result<Vec<response::PlateHistory>, Error> {
+        self.answer(Endpoint::Plate, &[plate.as_str()])
+    }
+
+    async fn search_plate_by_dni(&self, dni: Dni) -> Result<Vec<response::PlateHistory>, Error> {
+        self.answer(Endpoint::PlateByDni, &[dni.as_str()])
+    }
+
+    async fn search_leaks(&self, query: String) -> Result<Vec<response::BreachCredentials>, Error> {
+        self.answer(Endpoint::Leaks, &[&query])
+    }
+
+    async fn search_profesional_dni(
+        &self,
+        dni: Dni,
+        gender: Gender,
+    ) -> Result<response::DNIProfesional, Error> {
+        self.answer(Endpoint::ProfesionalDni, &[dni.as_str(), gender.as_str()])
+    }
+
+    async fn search_name(
+        &self,
+        name: String,
+        filters: Option<NameSearchFilters>,
+    ) -> Result<Vec<response::PersonaNombre>, Error> {
+        let mut args = vec![name];
+        if let Ok(Value::Object(params)) = serde_json::to_value(filters.unwrap_or_default()) {
+            let mut params: Vec<_> = params.into_iter().collect();
+            params.sort_by(|a, b| a.0.cmp(&b.0));
+            args.extend(params.into_iter().map(|(key, value)| match value {
+                Value::String(value) => format!("{}={}", key, value),
+                value => format!("{}={}", key, value),
+            }));
+        }
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.answer(Endpoint::Name, &args)
+    }
+
+    async fn search_movistar_email(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<response::MovistarEmail, Error> {
+        self.answer(Endpoint::MovistarEmail, &[number.as_str()])
+    }
+
+    async fn search_by_address(
+        &self,
+        address: String,
+    ) -> Result<Vec<response::PersonaDireccion>, Error> {
+        self.answer(Endpoint::Address, &[&address])
+    }
+
+    async fn search_phone(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<Vec<response::PersonaFromNumero>, Error> {
+        self.answer(Endpoint::Phone, &[number.as_str()])
+    }
+
+    async fn search_phone_magic(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<response::PersonaFromNumeroMagic, Error> {
+        self.answer(Endpoint::PhoneMagic, &[number.as_str()])
+    }
+
+    async fn search_cbu(&self, cbu_or_alias: CbuOrAlias) -> Result<response::TitularCBU, Error> {
+        self.answer(Endpoint::Cbu, &[&cbu_or_alias.to_string()])
+    }
+
+    async fn search_email(&self, email: String) -> Result<response::EmailResultados, Error> {
+        self.answer(Endpoint::Email, &[&email])
+    }
+}
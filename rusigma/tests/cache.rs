@@ -0,0 +1,173 @@
+#![cfg(feature = "cache")]
+
+mod common;
+
+use common::*;
+use rusigma::cache::{self, ResponseCache, MAX_RETENTION};
+use rusigma::document::Dni;
+use rusigma::endpoints::Endpoint;
+use rusigma::limits::QueryBudget;
+use rusigma::SigmaClient;
+use std::path::PathBuf;
+use std::time::Duration;
+use wiremock::matchers::path;
+use wiremock::{Mock, MockServer};
+
+const DNI_PATH: &str = "/api/v2/standard/osint/argentina/resolver/dni";
+
+/// Empty cache directory of one test
+fn cache_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sigma-cache-{}-{}", test, std::process::id()));
+    cache::purge(&dir).unwrap();
+    dir
+}
+
+fn client_with(server: &MockServer, cache: ResponseCache) -> SigmaClient {
+    client(PLAN_STANDARD, |builder| {
+        builder
+            .base_url(&server.uri())
+            .query_budget(QueryBudget::new())
+            .cache(cache)
+    })
+}
+
+async fn expect_queries(server: &MockServer, times: u64) {
+    Mock::given(path(DNI_PATH))
+        .respond_with(ok(dni_standard()))
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn repeated_queries_are_answered_from_the_cache() -> anyhow::Result<()> {
+    let dir = cache_dir("repeated");
+    let server = MockServer::start().await;
+    expect_queries(&server, 1).await;
+    let client = client_with(&server, ResponseCache::open(&dir, "clave")?);
+
+    let first = client.search_standard_dni(dni()).await?;
+    let second = client
+        .search_standard_dni("30.123.456".parse::<Dni>()?)
+        .await?;
+    assert_eq!(first, second);
+    let budget = client.http.query_budget().unwrap();
+    assert_eq!(budget.used(Endpoint::StandardDni), 1);
+    cache::purge(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn servers_never_share_entries() -> anyhow::Result<()> {
+    let dir = cache_dir("servers");
+    let first = MockServer::start().await;
+    let second = MockServer::start().await;
+    expect_queries(&first, 1).await;
+    expect_queries(&second, 1).await;
+
+    client_with(&first, ResponseCache::open(&dir, "clave")?)
+        .search_standard_dni(dni())
+        .await?;
+    client_with(&second, ResponseCache::open(&dir, "clave")?)
+        .search_standard_dni(dni())
+        .await?;
+    cache::purge(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn entries_are_encrypted_and_bound_to_the_passphrase() -> anyhow::Result<()> {
+    let dir = cache_dir("passphrase");
+    let server = MockServer::start().await;
+    expect_queries(&server, 2).await;
+
+    let client = client_with(&server, ResponseCache::open(&dir, "clave")?);
+    client.search_standard_dni(dni()).await?;
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        let content = String::from_utf8_lossy(&std::fs::read(entry.path())?).to_string();
+        assert!(!content.contains("PEREZ"));
+        assert!(!entry.file_name().to_string_lossy().contains("30123456"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(entry.metadata()?.permissions().mode() & 0o077, 0);
+        }
+    }
+
+    let client = client_with(&server, ResponseCache::open(&dir, "otra clave")?);
+    client.search_standard_dni(dni()).await?;
+    cache::purge(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn refresh_queries_again_and_updates_the_entry() -> anyhow::Result<()> {
+    let dir = cache_dir("refresh");
+    let server = MockServer::start().await;
+    expect_queries(&server, 2).await;
+
+    let client = client_with(&server, ResponseCache::open(&dir, "clave")?);
+    client.search_standard_dni(dni()).await?;
+    let cache = ResponseCache::open(&dir, "clave")?.refresh(true);
+    let client = client_with(&server, cache);
+    client.search_standard_dni(dni()).await?;
+    let client = client_with(&server, ResponseCache::open(&dir, "clave")?);
+    client.search_standard_dni(dni()).await?;
+    cache::purge(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn endpoints_with_zero_ttl_are_not_cached() -> anyhow::Result<()> {
+    let dir = cache_dir("ttl");
+    let server = MockServer::start().await;
+    expect_queries(&server, 2).await;
+
+    let cache = ResponseCache::open(&dir, "clave")?.ttl(Endpoint::StandardDni, Duration::ZERO);
+    let client = client_with(&server, cache);
+    client.search_standard_dni(dni()).await?;
+    client.search_standard_dni(dni()).await?;
+    cache::purge(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn old_entries_are_pruned_when_storing() -> anyhow::Result<()> {
+    let dir = cache_dir("prune");
+    let server = MockServer::start().await;
+    expect_queries(&server, 2).await;
+
+    let cache = ResponseCache::open(&dir, "clave")?.max_retention(Duration::from_millis(100));
+    let client = client_with(&server, cache);
+    client.search_standard_dni(dni()).await?;
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    client
+        .search_standard_dni("20111222".parse::<Dni>()?)
+        .await?;
+    let entries = std::fs::read_dir(&dir)?
+        .filter(|entry| {
+            let path = entry.as_ref().unwrap().path();
+            path.extension().is_some_and(|ext| ext == "entry")
+        })
+        .count();
+    assert_eq!(entries, 1);
+    cache::purge(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn ttls_never_exceed_the_maximum_retention() -> anyhow::Result<()> {
+    let dir = cache_dir("retention");
+    let cache = ResponseCache::open(&dir, "clave")?
+        .default_ttl(Duration::from_secs(7 * 24 * 3600))
+        .ttl(Endpoint::Plate, Duration::from_secs(600));
+    assert_eq!(cache.ttl_for(Endpoint::StandardDni), MAX_RETENTION);
+    assert_eq!(cache.ttl_for(Endpoint::Plate), Duration::from_secs(600));
+
+    let cache = cache.max_retention(Duration::from_secs(300));
+    assert_eq!(cache.ttl_for(Endpoint::Plate), Duration::from_secs(300));
+    cache.purge()?;
+    assert!(!dir.exists());
+    Ok(())
+}
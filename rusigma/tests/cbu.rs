@@ -0,0 +1,69 @@
+use rusigma::cbu::{Alias, Cbu, CbuOrAlias};
+use rusigma::Error;
+
+const GALICIA: &str = "0070999003000412345671";
+const CVU: &str = "0000003100000000123459";
+
+fn invalid<T: std::fmt::Debug>(result: Result<T, Error>) -> String {
+    match result {
+        Err(Error::InvalidInput(reason)) => reason,
+        other => panic!("expected an invalid input error, got {:?}", other),
+    }
+}
+
+#[test]
+fn cbu_verifies_both_check_digits() {
+    let cbu: Cbu = GALICIA.parse().unwrap();
+    assert!(!cbu.is_cvu());
+    assert_eq!(cbu.entity_code(), "007");
+    assert_eq!(cbu.bank_name(), Some("Banco de Galicia"));
+
+    let mut wrong_first = GALICIA.to_string();
+    wrong_first.replace_range(7..8, "5");
+    assert!(invalid(wrong_first.parse::<Cbu>()).contains("branch block"));
+
+    let mut wrong_second = GALICIA.to_string();
+    wrong_second.replace_range(21..22, "0");
+    assert!(invalid(wrong_second.parse::<Cbu>()).contains("account block"));
+
+    assert!(invalid(GALICIA[..21].parse::<Cbu>()).contains("22 digits"));
+}
+
+#[test]
+fn cvu_is_told_apart_from_bank_cbu() {
+    let cvu: Cbu = CVU.parse().unwrap();
+    assert!(cvu.is_cvu());
+    assert_eq!(cvu.entity_code(), "0003");
+    assert_eq!(cvu.bank_name(), None);
+}
+
+#[test]
+fn alias_syntax_is_checked() {
+    assert_eq!(
+        "juan.perez-mp".parse::<Alias>().unwrap().as_str(),
+        "juan.perez-mp"
+    );
+    assert!(invalid("corto".parse::<Alias>()).contains("6 to 20"));
+    assert!(invalid("un.alias.demasiado.largo".parse::<Alias>()).contains("6 to 20"));
+    assert!(invalid("juan perez".parse::<Alias>()).contains("' '"));
+}
+
+#[test]
+fn cbu_or_alias_picks_the_right_kind() {
+    assert!(matches!(
+        GALICIA.parse::<CbuOrAlias>().unwrap(),
+        CbuOrAlias::Cbu(_)
+    ));
+    assert!(matches!(
+        "juan.perez.mp".parse::<CbuOrAlias>().unwrap(),
+        CbuOrAlias::Alias(_)
+    ));
+    assert!(invalid("12345678".parse::<CbuOrAlias>()).contains("22 digits"));
+    assert_eq!(
+        "0070999 0030004 12345671"
+            .parse::<CbuOrAlias>()
+            .unwrap()
+            .to_string(),
+        GALICIA
+    );
+}
@@ -0,0 +1,129 @@
+use super::setup;
+use async_trait::async_trait;
+use rusigma::blocking::SigmaClient;
+use rusigma::cbu::CbuOrAlias;
+use rusigma::document::{Dni, Gender};
+use rusigma::filters::NameSearchFilters;
+use rusigma::phone::PhoneNumber;
+use rusigma::plate::Plate;
+use rusigma::{response, Error, Plan, SigmaApi};
+use wiremock::MockServer;
+
+/// Blocking client behind `SigmaApi`, so the endpoint tests run against it.
+/// Every call runs on a blocking thread, outside the runtime of the mock
+/// server, where the blocking client would panic.
+pub struct BlockingApi(Option<SigmaClient>);
+
+/// Like `setup`, with the async client wrapped in a blocking one
+pub async fn setup_blocking(plan: Plan) -> (MockServer, BlockingApi) {
+    let (server, client) = setup(plan).await;
+    let client = SigmaClient::from_async(client).unwrap();
+    (server, BlockingApi(Some(client)))
+}
+
+impl BlockingApi {
+    async fn run<T, F>(&self, query: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SigmaClient) -> Result<T, Error> + Send + 'static,
+    {
+        let client = self.0.clone().unwrap();
+        tokio::task::spawn_blocking(move || query(&client))
+            .await
+            .unwrap()
+    }
+}
+
+/// Its runtime cannot be dropped from an async context either
+impl Drop for BlockingApi {
+    fn drop(&mut self) {
+        let client = self.0.take();
+        std::thread::spawn(move || drop(client)).join().unwrap();
+    }
+}
+
+#[async_trait]
+impl SigmaApi for BlockingApi {
+    async fn login_with_credentials(
+        &self,
+        username: String,
+        password: String,
+    ) -> Result<(), Error> {
+        self.run(|client| client.login_with_credentials(username, password))
+            .await
+    }
+
+    async fn search_standard_dni(&self, dni: Dni) -> Result<response::DNIStandardResponse, Error> {
+        self.run(|client| client.search_standard_dni(dni)).await
+    }
+
+    async fn search_phones_by_dni(&self, dni: Dni) -> Result<Vec<response::PhoneRecord>, Error> {
+        self.run(|client| client.search_phones_by_dni(dni)).await
+    }
+
+    async fn search_plate(&self, plate: Plate) -> Result<Vec<response::PlateHistory>, Error> {
+        self.run(|client| client.search_plate(plate)).await
+    }
+
+    async fn search_plate_by_dni(&self, dni: Dni) -> Result<Vec<response::PlateHistory>, Error> {
+        self.run(|client| client.search_plate_by_dni(dni)).await
+    }
+
+    async fn search_leaks(&self, query: String) -> Result<Vec<response::BreachCredentials>, Error> {
+        self.run(|client| client.search_leaks(query)).await
+    }
+
+    async fn search_profesional_dni(
+        &self,
+        dni: Dni,
+        gender: Gender,
+    ) -> Result<response::DNIProfesional, Error> {
+        self.run(move |client| client.search_profesional_dni(dni, gender))
+            .await
+    }
+
+    async fn search_name(
+        &self,
+        name: String,
+        filters: Option<NameSearchFilters>,
+    ) -> Result<Vec<response::PersonaNombre>, Error> {
+        self.run(|client| client.search_name(name, filters)).await
+    }
+
+    async fn search_movistar_email(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<response::MovistarEmail, Error> {
+        self.run(|client| client.search_movistar_email(number))
+            .await
+    }
+
+    async fn search_by_address(
+        &self,
+        address: String,
+    ) -> Result<Vec<response::PersonaDireccion>, Error> {
+        self.run(|client| client.search_by_address(address)).await
+    }
+
+    async fn search_phone(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<Vec<response::PersonaFromNumero>, Error> {
+        self.run(|client| client.search_phone(number)).await
+    }
+
+    async fn search_phone_magic(
+        &self,
+        number: PhoneNumber,
+    ) -> Result<response::PersonaFromNumeroMagic, Error> {
+        self.run(|client| client.search_phone_magic(number)).await
+    }
+
+    async fn search_cbu(&self, cbu_or_alias: CbuOrAlias) -> Result<response::TitularCBU, Error> {
+        self.run(|client| client.search_cbu(cbu_or_alias)).await
+    }
+
+    async fn search_email(&self, email: String) -> Result<response::EmailResultados, Error> {
+        self.run(|client| client.search_email(email)).await
+    }
+}
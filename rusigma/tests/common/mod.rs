@@ -0,0 +1,222 @@
+#![allow(dead_code)]
+
+use rusigma::document::Dni;
+use rusigma::phone::PhoneNumber;
+use rusigma::plate::Plate;
+use rusigma::{Plan, SigmaClient, SigmaClientBuilder};
+use serde_json::{json, Value};
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+pub const TOKEN: &str = "test-token";
+
+pub const PLAN_PROFESIONAL: Plan = Plan::Profesional;
+pub const PLAN_MEDIUM: Plan = Plan::Medium;
+pub const PLAN_STANDARD: Plan = Plan::Standard;
+
+/// Starts a mock Sigma server and a client logged in with the given plan
+pub async fn setup(plan: Plan) -> (MockServer, SigmaClient) {
+    setup_with(plan, |builder| builder).await
+}
+
+/// Like `setup`, with the options `configure` adds to the builder
+pub async fn setup_with<F>(plan: Plan, configure: F) -> (MockServer, SigmaClient)
+where
+    F: FnOnce(SigmaClientBuilder) -> SigmaClientBuilder,
+{
+    let server = MockServer::start().await;
+    let client = client(plan, |builder| configure(builder.base_url(&server.uri())));
+    (server, client)
+}
+
+/// Client logged in with the given plan, built without a mock server
+pub fn client<F>(plan: Plan, configure: F) -> SigmaClient
+where
+    F: FnOnce(SigmaClientBuilder) -> SigmaClientBuilder,
+{
+    let client = configure(SigmaClient::builder()).build().unwrap();
+    client.http.set_api_token(TOKEN, plan);
+    client
+}
+
+/// Expects exactly one authenticated POST on `path` with the given JSON body
+pub async fn expect(server: &MockServer, endpoint: &str, body: Value, response: ResponseTemplate) {
+    Mock::given(method("POST"))
+        .and(path(endpoint))
+        .and(header("sigma-key", TOKEN))
+        .and(body_json(body))
+        .respond_with(response)
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+pub fn dni() -> Dni {
+    "30123456".parse().unwrap()
+}
+
+pub fn phone() -> PhoneNumber {
+    "1145678901".parse().unwrap()
+}
+
+pub fn plate() -> Plate {
+    "AB123CD".parse().unwrap()
+}
+
+pub fn ok(body: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(body)
+}
+
+pub fn api_error(status: u16, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(json!({ "error": true, "mensaje": message }))
+}
+
+pub fn dni_standard() -> Value {
+    json!({
+        "doc": "30123456",
+        "apellido": "PEREZ",
+        "nombres": "JUAN CARLOS",
+        "calle": "AV SIEMPRE VIVA 742",
+        "seccion": "12",
+        "circuito": "120A",
+        "tipo_doc": "DNI",
+        "localidad": "CAPITAL FEDERAL",
+        "provincia": "CIUDAD AUTONOMA DE BUENOS AIRES",
+        "codigo_postal": "1405"
+    })
+}
+
+pub fn phone_numbers() -> Value {
+    json!([{
+        "doc": "30123456",
+        "numero": "1145678901",
+        "nombre": "PEREZ JUAN CARLOS",
+        "localidad": "CAPITAL FEDERAL",
+        "provincia": "CIUDAD AUTONOMA DE BUENOS AIRES",
+        "codigo_postal": "1405",
+        "empresa": "PERSONAL"
+    }])
+}
+
+pub fn plate_history() -> Value {
+    json!([{
+        "patente": "AB123CD",
+        "documento": "30123456",
+        "vehiculo": "SEDAN 4 PUERTAS",
+        "marca": "FIAT",
+        "anio": "2019",
+        "titular": "PEREZ JUAN CARLOS",
+        "porcentaje": "100",
+        "calle": "AV SIEMPRE VIVA",
+        "altura": "742",
+        "piso": null,
+        "depto": null,
+        "codigo_postal": "1405",
+        "localidad": "CAPITAL FEDERAL",
+        "transferencia": "2020-01-15"
+    }])
+}
+
+pub fn breach_credentials() -> Value {
+    json!([{ "usuario": "juan@example.com", "password": "hunter2" }])
+}
+
+pub fn dni_profesional() -> Value {
+    json!({
+        "emision": "2015-06-10",
+        "apellido": "PEREZ",
+        "nombres": "JUAN CARLOS",
+        "cuil": "20301234564",
+        "calle": "AV SIEMPRE VIVA",
+        "numero": "742",
+        "piso": null,
+        "departamento": null,
+        "barrio": "CABALLITO",
+        "monoblock": null,
+        "ciudad": "CIUDAD AUTONOMA DE BUENOS AIRES",
+        "municipio": "COMUNA 6",
+        "provincia": "CIUDAD AUTONOMA DE BUENOS AIRES",
+        "pais": "ARGENTINA",
+        "foto": null,
+        "tramite": "00512345678",
+        "documento": "30123456",
+        "fallecido": "NO",
+        "codigo_postal": "1405",
+        "cobertura": [{
+            "cobertura": "OSDE",
+            "nombre": "PEREZ JUAN CARLOS",
+            "dni": "30123456",
+            "sexo": "M"
+        }],
+        "fecha_nacimiento": "1983-04-21",
+        "edad": 40
+    })
+}
+
+pub fn personas_nombre() -> Value {
+    json!([{
+        "nombre": "PEREZ JUAN CARLOS",
+        "documento": "20301234564",
+        "provincia": "CORDOBA"
+    }])
+}
+
+pub fn movistar_email() -> Value {
+    json!({ "num": "1145678901", "email": "juan@example.com" })
+}
+
+pub fn personas_direccion() -> Value {
+    json!([{
+        "numero": "1145678901",
+        "doc": "30123456",
+        "nombre": "PEREZ JUAN CARLOS",
+        "direccion": "AV SIEMPRE VIVA 742",
+        "localidad": "CAPITAL FEDERAL",
+        "provincia": "CIUDAD AUTONOMA DE BUENOS AIRES",
+        "codigo_postal": "1405",
+        "empresa": "MOVISTAR"
+    }])
+}
+
+pub fn personas_numero() -> Value {
+    json!([{
+        "celular": "1145678901",
+        "documento": "30123456",
+        "nombre": "PEREZ JUAN CARLOS",
+        "direccion": "AV SIEMPRE VIVA 742",
+        "localidad": "CAPITAL FEDERAL",
+        "provincia": "CIUDAD AUTONOMA DE BUENOS AIRES",
+        "codigo_postal": "1405",
+        "empresa": "CLARO"
+    }])
+}
+
+pub fn persona_numero_magic() -> Value {
+    json!({
+        "nombre": "JUAN CARLOS",
+        "apellido": "PEREZ",
+        "email": "juan@example.com",
+        "numero": "1145678901"
+    })
+}
+
+pub fn titular_cbu() -> Value {
+    json!({
+        "nombre": "PEREZ JUAN CARLOS",
+        "cuit": "20301234564",
+        "banco": "BANCO DE GALICIA",
+        "cbu": "0070999030004123456789",
+        "cuenta_tipo": "CA"
+    })
+}
+
+pub fn email_resultados() -> Value {
+    json!({
+        "nombre": "JUAN CARLOS",
+        "apellido": "PEREZ",
+        "email": "juan@example.com"
+    })
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::*;
+use rusigma::{Plan, SigmaClient};
+use serde_json::json;
+use std::sync::Arc;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer};
+
+fn assert_send_sync_clone<T: Send + Sync + Clone + 'static>() {}
+
+#[test]
+fn client_is_send_sync_and_clone() {
+    assert_send_sync_clone::<SigmaClient>();
+    assert_send_sync_clone::<rusigma::http::HTTPClient>();
+}
+
+#[tokio::test]
+async fn shared_client_serves_concurrent_tasks() {
+    let (server, client) = setup(PLAN_STANDARD).await;
+    Mock::given(method("POST"))
+        .respond_with(ok(dni_standard()))
+        .expect(20)
+        .mount(&server)
+        .await;
+
+    let client = Arc::new(client);
+    let tasks: Vec<_> = (0..20)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                client
+                    .search_standard_dni(dni())
+                    .await
+                    .map(|result| result.documento)
+            })
+        })
+        .collect();
+    for task in tasks {
+        assert_eq!(task.await.unwrap().unwrap(), "30123456");
+    }
+}
+
+#[tokio::test]
+async fn clones_share_the_session() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/sigma/client/login"))
+        .respond_with(ok(json!({ "token": "fresh-token", "plan": 3 })))
+        .mount(&server)
+        .await;
+
+    let client = SigmaClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let clone = client.clone();
+    client
+        .login_with_credentials("juan".to_string(), "secreto".to_string())
+        .await
+        .unwrap();
+    assert_eq!(clone.http.token().as_deref(), Some("fresh-token"));
+    assert_eq!(clone.http.plan(), Some(Plan::Standard));
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use common::*;
+use rusigma::credentials::{Credentials, CredentialsProvider, FileCredentials};
+use rusigma::http::Session;
+use rusigma::{Error, SigmaClient};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer};
+
+const DNI_PATH: &str = "/api/v2/standard/osint/argentina/resolver/dni";
+const LOGIN_PATH: &str = "/api/sigma/client/login";
+
+/// Client with an expired token whose queries are only accepted with `fresh-token`
+async fn expired_session<F>(configure: F) -> (MockServer, SigmaClient, Arc<Mutex<Vec<Session>>>)
+where
+    F: FnOnce(rusigma::SigmaClientBuilder) -> rusigma::SigmaClientBuilder,
+{
+    let refreshed = Arc::new(Mutex::new(Vec::new()));
+    let sink = refreshed.clone();
+    let (server, client) = setup_with(PLAN_STANDARD, |builder| {
+        configure(builder)
+            .on_token_refreshed(move |session| sink.lock().unwrap().push(session.clone()))
+    })
+    .await;
+    Mock::given(method("POST"))
+        .and(path(DNI_PATH))
+        .and(header("sigma-key", TOKEN))
+        .respond_with(api_error(401, "token vencido"))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(DNI_PATH))
+        .and(header("sigma-key", "fresh-token"))
+        .respond_with(ok(dni_standard()))
+        .mount(&server)
+        .await;
+    (server, client, refreshed)
+}
+
+async fn expect_login(server: &MockServer, times: u64) {
+    Mock::given(method("POST"))
+        .and(path(LOGIN_PATH))
+        .and(body_json(
+            json!({ "username": "juan", "password": "secreto" }),
+        ))
+        .respond_with(ok(json!({ "token": "fresh-token", "plan": 3 })))
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn expired_token_is_replaced_and_the_query_retried() -> anyhow::Result<()> {
+    let (server, client, refreshed) = expired_session(|builder| {
+        builder.credentials_provider(Credentials::new("juan", "secreto"))
+    })
+    .await;
+    expect_login(&server, 1).await;
+
+    let result = client.search_standard_dni(dni()).await?;
+    assert_eq!(result.documento, "30123456");
+    assert_eq!(client.http.token().as_deref(), Some("fresh-token"));
+    assert_eq!(
+        *refreshed.lock().unwrap(),
+        [Session {
+            token: Some("fresh-token".to_string()),
+            plan: Some(PLAN_STANDARD),
+        }]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn without_provider_the_rejection_is_returned() {
+    let (server, client, refreshed) = expired_session(|builder| builder).await;
+    expect_login(&server, 0).await;
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::Unauthorized { .. })));
+    assert!(refreshed.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn the_query_is_retried_only_once() {
+    let (server, client) = setup_with(PLAN_STANDARD, |builder| {
+        builder.credentials_provider(Credentials::new("juan", "secreto"))
+    })
+    .await;
+    Mock::given(method("POST"))
+        .and(path(DNI_PATH))
+        .respond_with(api_error(401, "token vencido"))
+        .expect(2)
+        .mount(&server)
+        .await;
+    expect_login(&server, 1).await;
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::Unauthorized { .. })));
+}
+
+#[tokio::test]
+async fn provider_failures_keep_the_rejection() {
+    let (server, client, _) = expired_session(|builder| {
+        builder
+            .credentials_provider(|| Err(Error::CredentialsUnavailable("no terminal".to_string())))
+    })
+    .await;
+    expect_login(&server, 0).await;
+
+    match client.search_standard_dni(dni()).await {
+        Err(Error::Unauthorized { status, message }) => {
+            assert_eq!(status, 401);
+            assert!(message.contains("token vencido"));
+            assert!(message.contains("no terminal"));
+        }
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn concurrent_rejections_log_in_once() -> anyhow::Result<()> {
+    let (server, client, refreshed) = expired_session(|builder| {
+        builder.credentials_provider(Credentials::new("juan", "secreto"))
+    })
+    .await;
+    expect_login(&server, 1).await;
+
+    let other = client.clone();
+    let (first, second) = tokio::join!(
+        client.search_standard_dni(dni()),
+        other.search_standard_dni(dni())
+    );
+    first?;
+    second?;
+    assert_eq!(refreshed.lock().unwrap().len(), 1);
+    Ok(())
+}
+
+/// Credentials file readable only by the current user
+fn private_file(name: &str, contents: &str) -> std::io::Result<std::path::PathBuf> {
+    let path = std::env::temp_dir().join(format!("sigma-{}-{}", name, std::process::id()));
+    std::fs::write(&path, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(path)
+}
+
+#[tokio::test]
+async fn file_credentials_are_read_and_not_echoed() -> anyhow::Result<()> {
+    let path = private_file(
+        "credentials",
+        "# cuenta de prueba\nusername = juan\npassword=secreto\n",
+    )?;
+    let credentials = FileCredentials::new(&path).credentials().await?;
+    assert_eq!(credentials, Credentials::new("juan", "secreto"));
+    assert!(!format!("{:?}", credentials).contains("secreto"));
+
+    std::fs::write(&path, "username=juan\npasword=secreto\n")?;
+    let error = FileCredentials::new(&path).credentials().await.unwrap_err();
+    assert!(!error.to_string().contains("secreto"));
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn file_credentials_readable_by_others_are_refused() -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = private_file("shared-credentials", "username=juan\npassword=secreto\n")?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))?;
+
+    let result = FileCredentials::new(&path).credentials().await;
+    assert!(matches!(result, Err(Error::CredentialsUnavailable(_))));
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use common::*;
+use rusigma::decode::{DecodeMode, Warning};
+use rusigma::document::Gender;
+use rusigma::endpoints::Endpoint;
+use rusigma::{Error, SigmaClient};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer};
+
+async fn client_with(
+    mode: DecodeMode,
+    body: Value,
+) -> (MockServer, SigmaClient, Arc<Mutex<Vec<Warning>>>) {
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let sink = warnings.clone();
+    let (server, client) = setup_with(PLAN_PROFESIONAL, |builder| {
+        builder
+            .decode_mode(mode)
+            .on_warning(move |warning| sink.lock().unwrap().push(warning.clone()))
+    })
+    .await;
+    Mock::given(method("POST"))
+        .respond_with(ok(body))
+        .mount(&server)
+        .await;
+    (server, client, warnings)
+}
+
+#[tokio::test]
+async fn lenient_mode_repairs_inconsistent_fields() -> anyhow::Result<()> {
+    let mut body = personas_numero();
+    body[0]["documento"] = json!(30123456);
+    body[0]["empresa"] = json!("");
+    body[0]["localidad"] = Value::Null;
+    body[0].as_object_mut().unwrap().remove("provincia");
+    let (_server, client, warnings) = client_with(DecodeMode::Lenient, body).await;
+
+    let result = client.search_phone(phone()).await?;
+    assert_eq!(result[0].documento.as_deref(), Some("30123456"));
+    assert_eq!(result[0].empresa, None);
+    assert_eq!(result[0].localidad, None);
+    assert_eq!(result[0].provincia, None);
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        [
+            Warning::FieldDropped {
+                path: "[0].empresa".to_string(),
+                value: "\"\"".to_string(),
+                reason: "blank string read as missing".to_string(),
+            },
+            Warning::FieldCoerced {
+                path: "[0].documento".to_string(),
+                value: "30123456".to_string(),
+            },
+            Warning::SchemaDrift {
+                endpoint: Some(Endpoint::Phone),
+                added: Vec::new(),
+                missing: vec!["[].provincia".to_string()],
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn lenient_mode_reads_numbers_sent_as_strings() -> anyhow::Result<()> {
+    let mut body = dni_profesional();
+    body["edad"] = json!("45");
+    let (_server, client, _) = client_with(DecodeMode::Lenient, body).await;
+
+    let result = client.search_profesional_dni(dni(), Gender::Male).await?;
+    assert_eq!(result.edad, Some(45));
+    Ok(())
+}
+
+#[tokio::test]
+async fn lenient_mode_reports_dropped_fields() -> anyhow::Result<()> {
+    let mut body = dni_profesional();
+    body["edad"] = json!("cuarenta");
+    let (_server, client, warnings) = client_with(DecodeMode::Lenient, body).await;
+
+    let result = client.search_profesional_dni(dni(), Gender::Male).await?;
+    assert_eq!(result.edad, None);
+    match &warnings.lock().unwrap()[..] {
+        [Warning::FieldDropped { path, value, .. }] => {
+            assert_eq!(path, "edad");
+            assert_eq!(value, "\"cuarenta\"");
+        }
+        other => panic!("unexpected warnings: {:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn lenient_mode_keeps_blank_required_strings() -> anyhow::Result<()> {
+    let mut body = dni_standard();
+    body["calle"] = json!("");
+    body["codigo_postal"] = json!(" ");
+    let (_server, client, warnings) = client_with(DecodeMode::Lenient, body).await;
+    client.http.set_api_token(TOKEN, PLAN_STANDARD);
+
+    let result = client.search_standard_dni(dni()).await?;
+    assert_eq!(result.calle, "");
+    assert_eq!(result.codigo_postal, None);
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        [Warning::FieldDropped {
+            path: "codigo_postal".to_string(),
+            value: "\" \"".to_string(),
+            reason: "blank string read as missing".to_string(),
+        }]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn strict_mode_rejects_mismatched_types() {
+    let mut body = dni_profesional();
+    body["edad"] = json!("45");
+    let (_server, client, warnings) = client_with(DecodeMode::Strict, body).await;
+
+    let result = client.search_profesional_dni(dni(), Gender::Male).await;
+    assert!(matches!(result, Err(Error::DeserealizationError(_))));
+    assert!(warnings.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn lenient_mode_still_fails_on_missing_required_fields() {
+    let (_server, client, _) = client_with(DecodeMode::Lenient, json!({ "doc": "30123456" })).await;
+    client.http.set_api_token(TOKEN, PLAN_STANDARD);
+
+    match client.search_standard_dni(dni()).await {
+        Err(Error::DeserealizationError(message)) => assert!(message.contains("apellido")),
+        other => panic!("expected DeserealizationError, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn unknown_fields_are_kept_and_reported_as_drift() -> anyhow::Result<()> {
+    let mut body = dni_profesional();
+    body["ejemplar"] = json!("B");
+    body.as_object_mut().unwrap().remove("edad");
+    let (_server, client, warnings) = client_with(DecodeMode::Strict, body).await;
+
+    let result = client.search_profesional_dni(dni(), Gender::Male).await?;
+    assert_eq!(result.extra["ejemplar"], "B");
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        [Warning::SchemaDrift {
+            endpoint: Some(Endpoint::ProfesionalDni),
+            added: vec!["ejemplar".to_string()],
+            missing: vec!["edad".to_string()],
+        }]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn nested_drift_is_reported_once_per_field() -> anyhow::Result<()> {
+    let mut body = phone_numbers();
+    for record in body.as_array_mut().unwrap() {
+        record["operador"] = json!("Personal");
+    }
+    let (_server, client, warnings) = client_with(DecodeMode::Lenient, body).await;
+
+    let result = client.search_phones_by_dni(dni()).await?;
+    assert!(result
+        .iter()
+        .all(|record| record.extra["operador"] == "Personal"));
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        [Warning::SchemaDrift {
+            endpoint: Some(Endpoint::PhonesByDni),
+            added: vec!["[].operador".to_string()],
+            missing: Vec::new(),
+        }]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn blanks_of_undeclared_fields_are_kept_in_extra() -> anyhow::Result<()> {
+    let mut body = phone_numbers();
+    body[0]["operador"] = json!("");
+    let (_server, client, warnings) = client_with(DecodeMode::Lenient, body).await;
+
+    let result = client.search_phones_by_dni(dni()).await?;
+    assert_eq!(result[0].extra["operador"], "");
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        [Warning::SchemaDrift {
+            endpoint: Some(Endpoint::PhonesByDni),
+            added: vec!["[].operador".to_string()],
+            missing: Vec::new(),
+        }]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn strict_mode_is_the_default() {
+    let (server, client) = setup(PLAN_PROFESIONAL).await;
+    let mut body = personas_numero();
+    body[0]["documento"] = json!(30123456);
+    Mock::given(method("POST"))
+        .respond_with(ok(body))
+        .mount(&server)
+        .await;
+
+    let result = client.search_phone(phone()).await;
+    assert!(matches!(result, Err(Error::DeserealizationError(_))));
+}
@@ -0,0 +1,92 @@
+use rusigma::document::{Cuit, Dni, Gender};
+use rusigma::Error;
+
+fn cuits(candidates: Vec<Cuit>) -> Vec<String> {
+    candidates.iter().map(|cuit| cuit.to_string()).collect()
+}
+
+#[test]
+fn dni_accepts_dotted_and_plain_input() {
+    let dni: Dni = "30.123.456".parse().unwrap();
+    assert_eq!(dni.as_str(), "30123456");
+    assert_eq!(" 7123456 ".parse::<Dni>().unwrap().as_str(), "7123456");
+    assert_eq!("07123456".parse::<Dni>().unwrap().as_str(), "7123456");
+}
+
+#[test]
+fn dni_rejects_bad_length_and_characters() {
+    for input in ["301234567", "12345", "", "30l23456", "30-123-456"] {
+        assert!(
+            matches!(input.parse::<Dni>(), Err(Error::InvalidInput(_))),
+            "{} was accepted",
+            input
+        );
+    }
+}
+
+#[test]
+fn cuit_verifies_check_digit() {
+    let cuit: Cuit = "20-12345678-6".parse().unwrap();
+    assert_eq!(cuit.as_str(), "20123456786");
+    assert_eq!(cuit.to_string(), "20-12345678-6");
+    assert_eq!(cuit.prefix(), 20);
+    assert!(!cuit.is_company());
+    assert_eq!(cuit.dni().unwrap().as_str(), "12345678");
+    assert!("30500000003".parse::<Cuit>().unwrap().is_company());
+
+    assert!(matches!(
+        "20-12345678-7".parse::<Cuit>(),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        "2012345678".parse::<Cuit>(),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        "21-12345678-6".parse::<Cuit>(),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn only_cuits_of_people_with_a_valid_dni_convert() {
+    let cuit: Cuit = "20-12345678-6".parse().unwrap();
+    assert_eq!(Dni::try_from(cuit).unwrap().as_str(), "12345678");
+    for input in ["30-50000000-3", "20-00012345-6"] {
+        let cuit: Cuit = input.parse().unwrap();
+        assert!(
+            matches!(Dni::try_from(&cuit), Err(Error::InvalidInput(_))),
+            "{} was converted",
+            input
+        );
+    }
+}
+
+#[test]
+fn cuit_candidates_follow_gender() {
+    let dni: Dni = "12345678".parse().unwrap();
+    assert_eq!(cuits(dni.cuit_candidates(Gender::Male))[0], "20-12345678-6");
+    assert_eq!(
+        cuits(dni.cuit_candidates(Gender::Female))[0],
+        "27-12345678-0"
+    );
+    let other = cuits(dni.cuit_candidates(Gender::Other));
+    assert_eq!(other[..2], ["20-12345678-6", "27-12345678-0"]);
+    for cuit in other {
+        assert!(cuit.parse::<Cuit>().is_ok());
+    }
+}
+
+#[test]
+fn cuit_candidates_fall_back_to_23() {
+    let male: Dni = "10000005".parse().unwrap();
+    assert_eq!(
+        cuits(male.cuit_candidates(Gender::Male))[0],
+        "23-10000005-9"
+    );
+    let female: Dni = "10000002".parse().unwrap();
+    assert_eq!(
+        cuits(female.cuit_candidates(Gender::Female))[0],
+        "23-10000002-4"
+    );
+}
@@ -0,0 +1,527 @@
+mod common;
+
+use common::*;
+use rusigma::cbu::CbuOrAlias;
+use rusigma::document::{Cuit, Gender};
+use rusigma::endpoints::{spec, Endpoint, EndpointSpec};
+use rusigma::filters::{NameSearchFilters, Province};
+use rusigma::http::Session;
+use rusigma::request::DatoRequest;
+use rusigma::{Error, SigmaApi, SigmaClient};
+use serde_json::json;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RESOLVER: &str = "/osint/argentina/resolver";
+
+fn url(plan: &str, endpoint: &str) -> String {
+    format!("/api/v2/{}{}", plan, endpoint)
+}
+
+/// Runs each endpoint test with the async client and, with the `blocking`
+/// feature, with the blocking one, both through `SigmaApi`
+macro_rules! with_both_clients {
+    ($($test:ident: $plan:expr,)*) => {$(
+        mod $test {
+            use super::*;
+
+            #[tokio::test]
+            async fn async_client() -> anyhow::Result<()> {
+                let (server, client) = setup($plan).await;
+                super::$test(&server, &client).await
+            }
+
+            #[cfg(feature = "blocking")]
+            #[tokio::test]
+            async fn blocking_client() -> anyhow::Result<()> {
+                let (server, client) = common::blocking::setup_blocking($plan).await;
+                super::$test(&server, &client).await
+            }
+        }
+    )*};
+}
+
+with_both_clients! {
+    search_standard_dni: PLAN_STANDARD,
+    search_phones_by_dni: PLAN_STANDARD,
+    search_plate: PLAN_MEDIUM,
+    search_plate_by_dni: PLAN_MEDIUM,
+    search_leaks: PLAN_MEDIUM,
+    search_profesional_dni: PLAN_PROFESIONAL,
+    search_profesional_dni_sends_gender_names: PLAN_PROFESIONAL,
+    search_name_with_filters: PLAN_PROFESIONAL,
+    search_name_sends_the_canonical_province_name: PLAN_PROFESIONAL,
+    search_name_without_filters: PLAN_PROFESIONAL,
+    search_movistar_email: PLAN_PROFESIONAL,
+    search_by_address: PLAN_PROFESIONAL,
+    search_phone: PLAN_PROFESIONAL,
+    search_phone_magic: PLAN_PROFESIONAL,
+    search_cbu: PLAN_PROFESIONAL,
+    search_email: PLAN_PROFESIONAL,
+    no_results_is_returned_as_not_found: PLAN_STANDARD,
+    malformed_success_body_is_a_deserialization_error: PLAN_STANDARD,
+    dni_searches_accept_a_cuit: PLAN_STANDARD,
+}
+
+async fn search_standard_dni(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("standard", &format!("{}/dni", RESOLVER)),
+        json!({ "dni": "30123456" }),
+        ok(dni_standard()),
+    )
+    .await;
+
+    let result = client.search_standard_dni(dni()).await?;
+    assert_eq!(result.documento, "30123456");
+    assert_eq!(result.apellido, "PEREZ");
+    assert_eq!(result.codigo_postal.as_deref(), Some("1405"));
+    Ok(())
+}
+
+async fn search_phones_by_dni(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("standard", &format!("{}/dni_celular", RESOLVER)),
+        json!({ "dni": "30123456" }),
+        ok(phone_numbers()),
+    )
+    .await;
+
+    let result = client.search_phones_by_dni(dni()).await?;
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].numero.as_deref(), Some("1145678901"));
+    assert_eq!(result[0].empresa.as_deref(), Some("PERSONAL"));
+    Ok(())
+}
+
+async fn search_plate(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("medium", &format!("{}/patente", RESOLVER)),
+        json!({ "patente": "AB123CD" }),
+        ok(plate_history()),
+    )
+    .await;
+
+    let result = client.search_plate(plate()).await?;
+    assert_eq!(result[0].patente.as_deref(), Some("AB123CD"));
+    assert_eq!(result[0].piso, None);
+    Ok(())
+}
+
+async fn search_plate_by_dni(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("medium", &format!("{}/patente_dni", RESOLVER)),
+        json!({ "dni": "30123456" }),
+        ok(plate_history()),
+    )
+    .await;
+
+    let result = client.search_plate_by_dni(dni()).await?;
+    assert_eq!(result[0].marca.as_deref(), Some("FIAT"));
+    Ok(())
+}
+
+async fn search_leaks(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("medium", "/osint/argentina/search_engine/data_breach"),
+        json!({ "query": "example.com" }),
+        ok(breach_credentials()),
+    )
+    .await;
+
+    let result = client.search_leaks("example.com".to_string()).await?;
+    assert_eq!(result[0].username, "juan@example.com");
+    assert_eq!(result[0].password, "hunter2");
+    Ok(())
+}
+
+async fn search_profesional_dni(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/dni_two", RESOLVER)),
+        json!({ "dato": "30123456:Masculino" }),
+        ok(dni_profesional()),
+    )
+    .await;
+
+    let result = client.search_profesional_dni(dni(), Gender::Male).await?;
+    assert_eq!(result.cuil.as_deref(), Some("20301234564"));
+    assert_eq!(result.edad, Some(40));
+    assert_eq!(
+        result.cobertura.unwrap()[0].cobertura.as_deref(),
+        Some("OSDE")
+    );
+    Ok(())
+}
+
+async fn search_profesional_dni_sends_gender_names(
+    server: &MockServer,
+    client: &impl SigmaApi,
+) -> anyhow::Result<()> {
+    for dato in ["30123456:Femenino", "30123456:Otro"] {
+        expect(
+            server,
+            &url("profesional", &format!("{}/dni_two", RESOLVER)),
+            json!({ "dato": dato }),
+            ok(dni_profesional()),
+        )
+        .await;
+    }
+
+    client.search_profesional_dni(dni(), Gender::Female).await?;
+    client.search_profesional_dni(dni(), Gender::Other).await?;
+    Ok(())
+}
+
+async fn search_name_with_filters(
+    server: &MockServer,
+    client: &impl SigmaApi,
+) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/nombre", RESOLVER)),
+        json!({
+            "nombre": "juan perez",
+            "provincia_nombre": "CORDOBA",
+            "edad_desde": "30",
+            "edad_hasta": "45"
+        }),
+        ok(personas_nombre()),
+    )
+    .await;
+
+    let filters = NameSearchFilters::builder()
+        .province(Province::Cordoba)
+        .min_age(30)
+        .max_age(45)
+        .build()?;
+    let result = client
+        .search_name("juan perez".to_string(), Some(filters))
+        .await?;
+    assert_eq!(result[0].provincia.as_deref(), Some("CORDOBA"));
+    Ok(())
+}
+
+async fn search_name_sends_the_canonical_province_name(
+    server: &MockServer,
+    client: &impl SigmaApi,
+) -> anyhow::Result<()> {
+    let typed = [
+        ("Tierra del Fuego", "TIERRA DEL FUEGO"),
+        ("Neuquén", "NEUQUEN"),
+    ];
+    for (_, sent) in typed {
+        expect(
+            server,
+            &url("profesional", &format!("{}/nombre", RESOLVER)),
+            json!({ "nombre": "juan perez", "provincia_nombre": sent }),
+            ok(personas_nombre()),
+        )
+        .await;
+    }
+
+    for (input, _) in typed {
+        let filters = NameSearchFilters::builder()
+            .province(input.parse::<Province>()?)
+            .build()?;
+        client
+            .search_name("juan perez".to_string(), Some(filters))
+            .await?;
+    }
+    Ok(())
+}
+
+async fn search_name_without_filters(
+    server: &MockServer,
+    client: &impl SigmaApi,
+) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/nombre", RESOLVER)),
+        json!({ "nombre": "juan perez" }),
+        ok(personas_nombre()),
+    )
+    .await;
+
+    client.search_name("juan perez".to_string(), None).await?;
+    Ok(())
+}
+
+async fn search_movistar_email(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/movistar", RESOLVER)),
+        json!({ "num": "1145678901" }),
+        ok(movistar_email()),
+    )
+    .await;
+
+    let result = client.search_movistar_email(phone()).await?;
+    assert_eq!(result.numero, "1145678901");
+    assert_eq!(result.email, "juan@example.com");
+    Ok(())
+}
+
+async fn search_by_address(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/direccion", RESOLVER)),
+        json!({ "direccion": "AV SIEMPRE VIVA 742" }),
+        ok(personas_direccion()),
+    )
+    .await;
+
+    let result = client
+        .search_by_address("AV SIEMPRE VIVA 742".to_string())
+        .await?;
+    assert_eq!(result[0].documento.as_deref(), Some("30123456"));
+    Ok(())
+}
+
+async fn search_phone(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/celular", RESOLVER)),
+        json!({ "num": "1145678901" }),
+        ok(personas_numero()),
+    )
+    .await;
+
+    let result = client.search_phone(phone()).await?;
+    assert_eq!(result[0].celular.as_deref(), Some("1145678901"));
+    Ok(())
+}
+
+async fn search_phone_magic(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/magic", RESOLVER)),
+        json!({ "dato": "1145678901", "tipo": "buscar_celular" }),
+        ok(persona_numero_magic()),
+    )
+    .await;
+
+    let result = client.search_phone_magic(phone()).await?;
+    assert_eq!(result.email, "juan@example.com");
+    Ok(())
+}
+
+async fn search_cbu(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/magic", RESOLVER)),
+        json!({ "dato": "juan.perez.mp", "tipo": "buscar_cbu_alias" }),
+        ok(titular_cbu()),
+    )
+    .await;
+
+    let result = client
+        .search_cbu("juan.perez.mp".parse::<CbuOrAlias>()?)
+        .await?;
+    assert_eq!(result.banco.as_deref(), Some("BANCO DE GALICIA"));
+    assert_eq!(result.cuenta_tipo.as_deref(), Some("CA"));
+    Ok(())
+}
+
+async fn search_email(server: &MockServer, client: &impl SigmaApi) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("profesional", &format!("{}/magic", RESOLVER)),
+        json!({ "dato": "juan@example.com", "tipo": "buscar_email" }),
+        ok(email_resultados()),
+    )
+    .await;
+
+    let result = client.search_email("juan@example.com".to_string()).await?;
+    assert_eq!(result.apellido.as_deref(), Some("PEREZ"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn custom_api_path_is_used() -> anyhow::Result<()> {
+    let server = wiremock::MockServer::start().await;
+    let client = SigmaClient::builder()
+        .base_url(&format!("{}/", server.uri()))
+        .api_path("api/v3/")
+        .build()?;
+    client.http.set_api_token(TOKEN, PLAN_STANDARD);
+    expect(
+        &server,
+        &format!("/api/v3/standard{}/dni", RESOLVER),
+        json!({ "dni": "30123456" }),
+        ok(dni_standard()),
+    )
+    .await;
+
+    client.search_standard_dni(dni()).await?;
+    Ok(())
+}
+
+async fn no_results_is_returned_as_not_found(
+    server: &MockServer,
+    client: &impl SigmaApi,
+) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("standard", &format!("{}/dni", RESOLVER)),
+        json!({ "dni": "30123456" }),
+        api_error(404, "No se encontraron resultados"),
+    )
+    .await;
+
+    match client.search_standard_dni(dni()).await {
+        Err(Error::NotFound { message }) => {
+            assert_eq!(message, "No se encontraron resultados");
+        }
+        other => panic!("expected NotFound, got {:?}", other),
+    }
+    Ok(())
+}
+
+async fn malformed_success_body_is_a_deserialization_error(
+    server: &MockServer,
+    client: &impl SigmaApi,
+) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("standard", &format!("{}/dni", RESOLVER)),
+        json!({ "dni": "30123456" }),
+        ok(json!({ "unexpected": true })),
+    )
+    .await;
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::DeserealizationError(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn requests_without_token_are_not_sent() {
+    let server = wiremock::MockServer::start().await;
+    let client = SigmaClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    client.http.set_session(Session {
+        token: None,
+        plan: Some(PLAN_STANDARD),
+    });
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::NoLoginToken)));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn login_stores_token_and_plan() -> anyhow::Result<()> {
+    let server = wiremock::MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/sigma/client/login"))
+        .and(body_json(
+            json!({ "username": "juan", "password": "secreto" }),
+        ))
+        .respond_with(ok(json!({ "token": "fresh-token", "plan": 2 })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = SigmaClient::builder().base_url(&server.uri()).build()?;
+    client
+        .login_with_credentials("juan".to_string(), "secreto".to_string())
+        .await?;
+    assert_eq!(client.http.token().as_deref(), Some("fresh-token"));
+    assert_eq!(client.http.plan(), Some(PLAN_MEDIUM));
+    Ok(())
+}
+
+#[tokio::test]
+async fn login_failure_is_returned_as_unauthorized() {
+    let server = wiremock::MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/sigma/client/login"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+            "error": true,
+            "mensaje": "Credenciales invalidas"
+        })))
+        .mount(&server)
+        .await;
+
+    let client = SigmaClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let result = client
+        .login_with_credentials("juan".to_string(), "mal".to_string())
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::Unauthorized { status: 401, .. })
+    ));
+    assert!(client.http.token().is_none());
+}
+
+#[test]
+fn registry_describes_every_endpoint_once() {
+    let mut names: Vec<_> = Endpoint::ALL.iter().map(|e| e.name()).collect();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), Endpoint::ALL.len());
+
+    let magic: Vec<_> = Endpoint::ALL
+        .iter()
+        .filter(|e| e.path() == format!("{}/magic", RESOLVER))
+        .map(|e| e.tipo())
+        .collect();
+    assert_eq!(
+        magic,
+        [
+            Some("buscar_celular"),
+            Some("buscar_cbu_alias"),
+            Some("buscar_email")
+        ]
+    );
+    assert_eq!(Endpoint::StandardDni.tipo(), None);
+    assert_eq!(<spec::Cbu as EndpointSpec>::ENDPOINT, Endpoint::Cbu);
+}
+
+#[tokio::test]
+async fn execute_runs_a_typed_endpoint() -> anyhow::Result<()> {
+    let (server, client) = setup(PLAN_PROFESIONAL).await;
+    expect(
+        &server,
+        &url("profesional", &format!("{}/magic", RESOLVER)),
+        json!({ "dato": "mi.alias", "tipo": "buscar_cbu_alias" }),
+        ok(titular_cbu()),
+    )
+    .await;
+
+    let result = client
+        .http
+        .execute::<spec::Cbu>(&DatoRequest {
+            dato: "mi.alias".to_string(),
+        })
+        .await?;
+    assert_eq!(result.cuit.as_deref(), Some("20301234564"));
+    Ok(())
+}
+
+async fn dni_searches_accept_a_cuit(
+    server: &MockServer,
+    client: &impl SigmaApi,
+) -> anyhow::Result<()> {
+    expect(
+        server,
+        &url("standard", &format!("{}/dni", RESOLVER)),
+        json!({ "dni": "12345678" }),
+        ok(dni_standard()),
+    )
+    .await;
+
+    let cuit: Cuit = "20-12345678-6".parse()?;
+    client.search_standard_dni(cuit.dni()?).await?;
+    Ok(())
+}
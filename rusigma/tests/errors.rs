@@ -0,0 +1,151 @@
+mod common;
+
+use common::*;
+use rusigma::{Error, SigmaClient};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn respond_with(response: ResponseTemplate) -> Result<(), Error> {
+    let (server, client) = setup(PLAN_STANDARD).await;
+    Mock::given(method("POST"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    client.search_standard_dni(dni()).await.map(|_| ())
+}
+
+#[tokio::test]
+async fn html_gateway_error_keeps_status_and_snippet() {
+    let page = format!(
+        "<html><body>502 Bad Gateway{}</body></html>",
+        "x".repeat(500)
+    );
+    let error = respond_with(ResponseTemplate::new(502).set_body_string(page))
+        .await
+        .unwrap_err();
+    match &error {
+        Error::ServerError { status, message } => {
+            assert_eq!(*status, 502);
+            assert!(message.starts_with("<html><body>502 Bad Gateway"));
+            assert!(message.ends_with("..."));
+            assert!(message.len() < 300);
+        }
+        other => panic!("expected ServerError, got {:?}", other),
+    }
+    assert_eq!(error.status(), Some(502));
+}
+
+#[tokio::test]
+async fn expired_token_is_unauthorized() {
+    let error = respond_with(api_error(401, "Token expirado"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Unauthorized { status: 401, .. }));
+    assert!(error.to_string().contains("Token expirado"));
+}
+
+#[tokio::test]
+async fn forbidden_is_plan_forbidden() {
+    let error = respond_with(api_error(403, "Plan insuficiente"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::PlanForbidden { .. }));
+}
+
+#[tokio::test]
+async fn rate_limit_reads_retry_after_seconds() {
+    let error = respond_with(
+        ResponseTemplate::new(429)
+            .insert_header("Retry-After", "30")
+            .set_body_string("Too Many Requests"),
+    )
+    .await
+    .unwrap_err();
+    match error {
+        Error::RateLimited {
+            retry_after,
+            message,
+        } => {
+            assert_eq!(retry_after, Some(Duration::from_secs(30)));
+            assert_eq!(message, "Too Many Requests");
+        }
+        other => panic!("expected RateLimited, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rate_limit_reads_retry_after_date() {
+    let date = httpdate::fmt_http_date(std::time::SystemTime::now() + Duration::from_secs(120));
+    let error =
+        respond_with(ResponseTemplate::new(429).insert_header("Retry-After", date.as_str()))
+            .await
+            .unwrap_err();
+    match error {
+        Error::RateLimited {
+            retry_after: Some(retry_after),
+            ..
+        } => assert!(retry_after > Duration::from_secs(100)),
+        other => panic!("expected RateLimited with a delay, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn other_json_errors_keep_the_response() {
+    let error = respond_with(api_error(400, "DNI invalido"))
+        .await
+        .unwrap_err();
+    match &error {
+        Error::ApiError { status, response } => {
+            assert_eq!(*status, 400);
+            assert_eq!(response.message, "DNI invalido");
+        }
+        other => panic!("expected ApiError, got {:?}", other),
+    }
+    assert_eq!(
+        error.to_string(),
+        "Error response from Sigma (HTTP 400): DNI invalido"
+    );
+}
+
+#[tokio::test]
+async fn other_non_json_errors_are_unexpected_responses() {
+    let error = respond_with(ResponseTemplate::new(400).set_body_string("Bad Request"))
+        .await
+        .unwrap_err();
+    match error {
+        Error::UnexpectedResponse { status, body } => {
+            assert_eq!(status, 400);
+            assert_eq!(body, "Bad Request");
+        }
+        other => panic!("expected UnexpectedResponse, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn slow_responses_time_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ok(dni_standard()).set_delay(Duration::from_secs(2)))
+        .mount(&server)
+        .await;
+    let client = SigmaClient::builder()
+        .base_url(&server.uri())
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    client.http.set_api_token(TOKEN, PLAN_STANDARD);
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::Timeout)));
+}
+
+#[tokio::test]
+async fn invalid_success_body_reports_a_snippet() {
+    let error = respond_with(ok(json!({ "doc": 1 }))).await.unwrap_err();
+    match error {
+        Error::DeserealizationError(message) => assert!(message.contains("{\"doc\":1}")),
+        other => panic!("expected DeserealizationError, got {:?}", other),
+    }
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::*;
+use rusigma::endpoints::Endpoint;
+use rusigma::testing::{FakeCall, FakeSigmaClient};
+use rusigma::{Error, Plan, SigmaApi};
+use std::sync::Arc;
+
+/// Code under test only knows about the trait
+async fn surname_of(api: &dyn SigmaApi, dni: &str) -> Result<String, Error> {
+    Ok(api.search_standard_dni(dni.parse()?).await?.apellido)
+}
+
+#[tokio::test]
+async fn returns_queued_responses_then_errors() {
+    let fake = FakeSigmaClient::new()
+        .respond(Endpoint::StandardDni, dni_standard())
+        .fail(Endpoint::StandardDni, Error::Timeout);
+
+    assert_eq!(surname_of(&fake, "30123456").await.unwrap(), "PEREZ");
+    assert!(matches!(
+        surname_of(&fake, "30123457").await,
+        Err(Error::Timeout)
+    ));
+    assert!(matches!(
+        surname_of(&fake, "30123458").await,
+        Err(Error::InvalidConfiguration(_))
+    ));
+    assert_eq!(
+        fake.calls(),
+        ["30123456", "30123457", "30123458"]
+            .iter()
+            .map(|dni| FakeCall {
+                endpoint: Endpoint::StandardDni,
+                args: vec![dni.to_string()],
+            })
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn always_answers_once_the_queue_is_empty() {
+    let fake = FakeSigmaClient::new()
+        .fail(Endpoint::Plate, Error::Timeout)
+        .always(Endpoint::Plate, plate_history());
+
+    assert!(fake.search_plate(plate()).await.is_err());
+    for _ in 0..3 {
+        let plates = fake.search_plate(plate()).await.unwrap();
+        assert_eq!(plates[0].marca.as_deref(), Some("FIAT"));
+    }
+    assert_eq!(fake.calls_to(Endpoint::Plate).len(), 4);
+}
+
+#[tokio::test]
+async fn enforces_plan_entitlements() {
+    let fake = FakeSigmaClient::new()
+        .plan(Plan::Standard)
+        .always(Endpoint::Email, email_resultados());
+
+    let result = fake.search_email("juan@example.com".to_string()).await;
+    assert!(matches!(
+        result,
+        Err(Error::PlanNotAllowed {
+            endpoint: Endpoint::Email,
+            plan: Plan::Standard
+        })
+    ));
+}
+
+#[tokio::test]
+async fn records_logins() {
+    let fake = FakeSigmaClient::new().login_result(Err(Error::Unauthorized {
+        status: 401,
+        message: "Credenciales invalidas".to_string(),
+    }));
+
+    assert!(fake
+        .login_with_credentials("juan".to_string(), "mal".to_string())
+        .await
+        .is_err());
+    assert!(fake
+        .login_with_credentials("juan".to_string(), "bien".to_string())
+        .await
+        .is_ok());
+    assert_eq!(fake.logins(), vec!["juan", "juan"]);
+}
+
+#[tokio::test]
+async fn real_and_fake_clients_are_interchangeable() {
+    let (server, client) = setup(PLAN_STANDARD).await;
+    expect(
+        &server,
+        "/api/v2/standard/osint/argentina/resolver/dni",
+        serde_json::json!({ "dni": "30123456" }),
+        ok(dni_standard()),
+    )
+    .await;
+    let fake = FakeSigmaClient::new().respond(Endpoint::StandardDni, dni_standard());
+
+    let apis: Vec<Arc<dyn SigmaApi>> = vec![Arc::new(client), Arc::new(fake)];
+    for api in apis {
+        assert_eq!(surname_of(api.as_ref(), "30123456").await.unwrap(), "PEREZ");
+    }
+}
@@ -0,0 +1,69 @@
+use rusigma::filters::{NameSearchFilters, Province, MAX_AGE};
+use rusigma::Error;
+use serde_json::json;
+
+#[test]
+fn filters_serialize_to_wire_keys() {
+    let filters = NameSearchFilters::builder()
+        .province(Province::Caba)
+        .locality(" Palermo ")
+        .min_age(18)
+        .max_age(65)
+        .build()
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&filters).unwrap(),
+        json!({
+            "provincia_nombre": "CIUDAD AUTONOMA DE BUENOS AIRES",
+            "localidad": "Palermo",
+            "edad_desde": "18",
+            "edad_hasta": "65"
+        })
+    );
+}
+
+#[test]
+fn unset_filters_are_not_sent() {
+    let filters = NameSearchFilters::builder().locality("  ").build().unwrap();
+    assert!(filters.is_empty());
+    assert_eq!(serde_json::to_value(&filters).unwrap(), json!({}));
+}
+
+#[test]
+fn age_range_is_validated() {
+    assert!(matches!(
+        NameSearchFilters::builder().min_age(50).max_age(40).build(),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        NameSearchFilters::builder().max_age(MAX_AGE + 1).build(),
+        Err(Error::InvalidInput(_))
+    ));
+    let filters = NameSearchFilters::builder()
+        .min_age(40)
+        .max_age(40)
+        .build()
+        .unwrap();
+    assert_eq!((filters.min_age(), filters.max_age()), (Some(40), Some(40)));
+}
+
+#[test]
+fn province_parses_common_spellings() {
+    assert_eq!("Córdoba".parse::<Province>().unwrap(), Province::Cordoba);
+    assert_eq!(
+        "entre-rios".parse::<Province>().unwrap(),
+        Province::EntreRios
+    );
+    assert_eq!("CABA".parse::<Province>().unwrap(), Province::Caba);
+    assert_eq!(
+        "capital federal".parse::<Province>().unwrap(),
+        Province::Caba
+    );
+    assert!(matches!(
+        "Narnia".parse::<Province>(),
+        Err(Error::InvalidInput(_))
+    ));
+    for province in Province::ALL {
+        assert_eq!(province.as_str().parse::<Province>().unwrap(), province);
+    }
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use common::*;
+use rusigma::endpoints::Endpoint;
+use rusigma::limits::{QueryBudget, RateLimiter};
+use rusigma::{Error, SigmaClient};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer};
+
+fn usage_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rusigma-{}-{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+async fn client_with_budget(budget: QueryBudget) -> (MockServer, SigmaClient) {
+    let (server, client) =
+        setup_with(PLAN_PROFESIONAL, |builder| builder.query_budget(budget)).await;
+    Mock::given(method("POST"))
+        .respond_with(ok(dni_standard()))
+        .mount(&server)
+        .await;
+    (server, client)
+}
+
+#[tokio::test]
+async fn budget_refuses_queries_over_the_limit() {
+    let (server, client) =
+        client_with_budget(QueryBudget::new().limit(Endpoint::StandardDni, 2)).await;
+
+    for _ in 0..2 {
+        client.search_standard_dni(dni()).await.unwrap();
+    }
+    let result = client.search_standard_dni(dni()).await;
+    match result {
+        Err(Error::BudgetExceeded { endpoint, limit }) => {
+            assert_eq!(endpoint, Endpoint::StandardDni);
+            assert_eq!(limit, 2);
+        }
+        other => panic!("expected BudgetExceeded, got {:?}", other),
+    }
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn budget_limits_are_per_endpoint() {
+    let (_server, client) = client_with_budget(
+        QueryBudget::new()
+            .default_limit(1)
+            .limit(Endpoint::StandardDni, 5),
+    )
+    .await;
+
+    client.search_standard_dni(dni()).await.unwrap();
+    client.search_standard_dni(dni()).await.unwrap();
+    let result = client.search_email("juan@example.com".to_string()).await;
+    assert!(!matches!(result, Err(Error::BudgetExceeded { .. })));
+    let result = client.search_email("juan@example.com".to_string()).await;
+    assert!(matches!(
+        result,
+        Err(Error::BudgetExceeded { limit: 1, .. })
+    ));
+}
+
+#[test]
+fn budget_usage_persists_between_runs() {
+    let path = usage_file("persist");
+    let budget = QueryBudget::new()
+        .limit(Endpoint::Plate, 2)
+        .persist_to(&path)
+        .unwrap();
+    budget.try_consume(Endpoint::Plate).unwrap();
+    budget.try_consume(Endpoint::Leaks).unwrap();
+
+    let budget = QueryBudget::new()
+        .limit(Endpoint::Plate, 2)
+        .persist_to(&path)
+        .unwrap();
+    assert_eq!(budget.used(Endpoint::Plate), 1);
+    assert_eq!(budget.used(Endpoint::Leaks), 1);
+    budget.try_consume(Endpoint::Plate).unwrap();
+    assert!(budget.try_consume(Endpoint::Plate).is_err());
+    assert_eq!(budget.used(Endpoint::Plate), 2);
+
+    let report = budget.report();
+    let plate = report
+        .iter()
+        .find(|usage| usage.endpoint == Endpoint::Plate)
+        .unwrap();
+    assert_eq!((plate.used, plate.limit), (2, Some(2)));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn queries_are_not_counted_when_saving_fails() {
+    let dir = usage_file("missing-dir");
+    let budget = QueryBudget::new()
+        .persist_to(dir.join("usage.json"))
+        .unwrap();
+
+    let result = budget.try_consume(Endpoint::Plate);
+    assert!(matches!(result, Err(Error::BudgetStorage(_))));
+    assert_eq!(budget.used(Endpoint::Plate), 0);
+}
+
+#[test]
+fn corrupt_usage_file_is_an_error() {
+    let path = usage_file("corrupt");
+    std::fs::write(&path, "not json").unwrap();
+    let result = QueryBudget::new().persist_to(&path);
+    assert!(matches!(result, Err(Error::BudgetStorage(_))));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn rate_limiter_allows_bursts_up_to_capacity() {
+    let limiter = RateLimiter::new(3, Duration::from_secs(10));
+    assert_eq!(limiter.try_acquire(), None);
+    assert_eq!(limiter.try_acquire(), None);
+    assert_eq!(limiter.try_acquire(), None);
+    let wait = limiter.try_acquire().unwrap();
+    assert!(wait > Duration::from_secs(9) && wait <= Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn rate_limiter_spaces_out_queries() {
+    let (server, client) = setup_with(PLAN_STANDARD, |builder| {
+        builder.rate_limiter(RateLimiter::new(1, Duration::from_millis(200)))
+    })
+    .await;
+    Mock::given(method("POST"))
+        .respond_with(ok(dni_standard()))
+        .mount(&server)
+        .await;
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        client.search_standard_dni(dni()).await.unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(380));
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::*;
+use rusigma::document::Gender;
+use rusigma::parsed::{NaiveDate, Parsed};
+use rusigma::response::DNIProfesional;
+use serde_json::json;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn dates_are_read_in_every_sigma_layout() {
+    for raw in [
+        "1983-04-21",
+        "21/04/1983",
+        "21-04-1983",
+        "1983-04-21T00:00:00",
+    ] {
+        let parsed = Parsed::<NaiveDate>::new(raw);
+        assert_eq!(parsed.get(), Some(date(1983, 4, 21)), "{}", raw);
+        assert_eq!(parsed.raw(), raw);
+    }
+}
+
+#[test]
+fn unparseable_values_keep_the_raw_text() {
+    let mut body = dni_profesional();
+    body["emision"] = json!("SIN DATOS");
+    body["fallecido"] = json!("DESCONOCIDO");
+    let result: DNIProfesional = serde_json::from_value(body).unwrap();
+
+    let emision = result.emision.as_ref().unwrap();
+    assert!(!emision.is_parsed());
+    assert_eq!(emision.raw(), "SIN DATOS");
+    assert_eq!(result.is_deceased(), None);
+    assert_eq!(
+        serde_json::to_value(&result).unwrap()["fallecido"],
+        "DESCONOCIDO"
+    );
+}
+
+#[test]
+fn typed_fields_of_the_profesional_response() {
+    let mut body = dni_profesional();
+    body["fallecido"] = json!("Si");
+    let result: DNIProfesional = serde_json::from_value(body).unwrap();
+
+    assert_eq!(result.emision.unwrap().get(), Some(date(2015, 6, 10)));
+    assert_eq!(result.fallecido.unwrap().get(), Some(true));
+    let cobertura = &result.cobertura.unwrap()[0];
+    assert_eq!(cobertura.sexo.as_ref().unwrap().get(), Some(Gender::Male));
+}
+
+#[test]
+fn age_is_computed_from_the_birth_date_when_missing() {
+    let mut body = dni_profesional();
+    body["edad"] = json!(null);
+    let result: DNIProfesional = serde_json::from_value(body).unwrap();
+
+    assert_eq!(result.age_on(date(2023, 4, 20)), Some(39));
+    assert_eq!(result.age_on(date(2023, 4, 21)), Some(40));
+    assert!(result.age().unwrap() >= 40);
+}
+
+#[test]
+fn genders_are_parsed_from_letters_and_names() {
+    assert_eq!("m".parse::<Gender>().unwrap(), Gender::Male);
+    assert_eq!("Femenino".parse::<Gender>().unwrap(), Gender::Female);
+    assert_eq!("X".parse::<Gender>().unwrap(), Gender::Other);
+    assert_eq!("3".parse::<Gender>().unwrap(), Gender::Other);
+    assert!("Z".parse::<Gender>().is_err());
+}
@@ -0,0 +1,60 @@
+use rusigma::phone::PhoneNumber;
+use rusigma::Error;
+
+fn national(input: &str) -> String {
+    match input.parse::<PhoneNumber>() {
+        Ok(number) => number.to_string(),
+        Err(e) => panic!("{} was rejected: {}", input, e),
+    }
+}
+
+#[test]
+fn usual_forms_share_one_national_format() {
+    for input in [
+        "+54 9 11 1234-5678",
+        "+54 11 1234 5678",
+        "0054 9 11 1234 5678",
+        "5491112345678",
+        "011 15 1234 5678",
+        "(011) 1234-5678",
+        "11 15 1234 5678",
+        "1112345678",
+    ] {
+        assert_eq!(national(input), "1112345678", "{}", input);
+    }
+}
+
+#[test]
+fn area_codes_of_every_length_are_recognized() {
+    let number: PhoneNumber = "0351 15 123 4567".parse().unwrap();
+    assert_eq!(number.area_code(), "351");
+    assert_eq!(number.subscriber_number(), "1234567");
+
+    let number: PhoneNumber = "02966 15 12 3456".parse().unwrap();
+    assert_eq!(number.as_str(), "2966123456");
+    assert_eq!(number.area_code(), "2966");
+
+    let number: PhoneNumber = "+54 9 11 1234 5678".parse().unwrap();
+    assert_eq!(number.area_code(), "11");
+    assert_eq!(number.international_mobile(), "+5491112345678");
+}
+
+#[test]
+fn impossible_numbers_are_rejected() {
+    for input in [
+        "1234 5678",
+        "11 1234 56789",
+        "+1 415 555 0100",
+        "0800 333 1234",
+        "15 1234 5678",
+        "1912345678",
+        "11-1234-567a",
+        "",
+    ] {
+        assert!(
+            matches!(input.parse::<PhoneNumber>(), Err(Error::InvalidInput(_))),
+            "{} was accepted",
+            input
+        );
+    }
+}
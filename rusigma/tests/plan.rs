@@ -0,0 +1,130 @@
+mod common;
+
+use common::*;
+use rusigma::endpoints::Endpoint;
+use rusigma::http::Session;
+use rusigma::{Error, Plan, SigmaClient};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer};
+
+#[test]
+fn plan_ids_round_trip() {
+    for plan in Plan::ALL {
+        assert_eq!(Plan::from_id(plan.id()), Some(plan));
+    }
+    assert_eq!(Plan::from_id(5), None);
+    assert!(matches!(Plan::try_from(42u8), Err(Error::UnknownPlan(_))));
+}
+
+#[test]
+fn plan_parses_names_and_ids() {
+    assert_eq!("medium".parse::<Plan>().unwrap(), Plan::Medium);
+    assert_eq!("Profesional".parse::<Plan>().unwrap(), Plan::Profesional);
+    assert_eq!("3".parse::<Plan>().unwrap(), Plan::Standard);
+    assert!("9".parse::<Plan>().is_err());
+    assert!("gold".parse::<Plan>().is_err());
+}
+
+#[test]
+fn plan_serializes_as_its_name() {
+    assert_eq!(
+        serde_json::to_value(Plan::Standard).unwrap(),
+        json!("standard")
+    );
+    let plan: Plan = serde_json::from_value(json!("comunidades")).unwrap();
+    assert_eq!(plan, Plan::Comunidades);
+}
+
+#[test]
+fn entitlements_follow_plan_tiers() {
+    assert!(Endpoint::StandardDni.is_allowed_for(Plan::Standard));
+    assert!(Endpoint::StandardDni.is_allowed_for(Plan::Profesional));
+    assert!(!Endpoint::Plate.is_allowed_for(Plan::Standard));
+    assert!(Endpoint::Plate.is_allowed_for(Plan::Medium));
+    assert!(!Endpoint::Email.is_allowed_for(Plan::Medium));
+    assert!(Endpoint::ALL
+        .iter()
+        .all(|endpoint| endpoint.is_allowed_for(Plan::Profesional)));
+    assert!(Endpoint::ALL
+        .iter()
+        .all(|endpoint| !endpoint.is_allowed_for(Plan::Free)));
+}
+
+#[tokio::test]
+async fn disallowed_endpoint_is_rejected_before_sending() {
+    let (server, client) = setup(PLAN_STANDARD).await;
+
+    let result = client.search_plate(plate()).await;
+    match result {
+        Err(Error::PlanNotAllowed { endpoint, plan }) => {
+            assert_eq!(endpoint, Endpoint::Plate);
+            assert_eq!(plan, Plan::Standard);
+        }
+        other => panic!("expected PlanNotAllowed, got {:?}", other),
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn missing_plan_does_not_panic() {
+    let server = MockServer::start().await;
+    let client = SigmaClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    client.http.set_session(Session {
+        token: Some(TOKEN.to_string()),
+        plan: None,
+    });
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::NoLoginToken)));
+}
+
+#[test]
+fn from_creds_reads_token_and_plan() {
+    let client = SigmaClient::from_creds("abc:3\n".to_string()).unwrap();
+    assert_eq!(client.http.token().as_deref(), Some("abc"));
+    assert_eq!(client.http.plan(), Some(Plan::Standard));
+
+    let client = SigmaClient::from_creds("abc:medium".to_string()).unwrap();
+    assert_eq!(client.http.plan(), Some(Plan::Medium));
+
+    let client = SigmaClient::from_creds("abc".to_string()).unwrap();
+    assert_eq!(client.http.plan(), None);
+}
+
+#[test]
+fn from_creds_rejects_malformed_credentials() {
+    assert!(matches!(
+        SigmaClient::from_creds("abc:77".to_string()),
+        Err(Error::UnknownPlan(_))
+    ));
+    for credentials in ["", ":3", "Bad Gateway:3"] {
+        assert!(matches!(
+            SigmaClient::from_creds(credentials.to_string()),
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn login_with_unknown_plan_is_an_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/sigma/client/login"))
+        .respond_with(ok(json!({ "token": "fresh-token", "plan": 12 })))
+        .mount(&server)
+        .await;
+
+    let client = SigmaClient::builder()
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let result = client
+        .login_with_credentials("juan".to_string(), "secreto".to_string())
+        .await;
+    assert!(matches!(result, Err(Error::UnknownPlan(_))));
+    assert!(client.http.token().is_none());
+}
@@ -0,0 +1,41 @@
+use rusigma::plate::{Plate, PlateFormat};
+use rusigma::response::PlateHistory;
+use rusigma::Error;
+use serde_json::json;
+
+#[test]
+fn every_format_is_recognized() {
+    for (input, value, format) in [
+        ("ABC123", "ABC123", PlateFormat::Legacy),
+        ("ab 123 cd", "AB123CD", PlateFormat::Mercosur),
+        ("123-abc", "123ABC", PlateFormat::LegacyMotorcycle),
+        ("A 123 BCD", "A123BCD", PlateFormat::MercosurMotorcycle),
+    ] {
+        let plate: Plate = input.parse().unwrap();
+        assert_eq!(plate.as_str(), value);
+        assert_eq!(plate.format(), format);
+    }
+    assert!("123ABC".parse::<Plate>().unwrap().is_motorcycle());
+    assert!(!"AB123CD".parse::<Plate>().unwrap().is_motorcycle());
+}
+
+#[test]
+fn anything_else_is_rejected() {
+    for input in ["AB1234", "ABCD12", "AB123C", "ÑBC123", "AB_123_CD", ""] {
+        assert!(
+            matches!(input.parse::<Plate>(), Err(Error::InvalidInput(_))),
+            "{} was accepted",
+            input
+        );
+    }
+}
+
+#[test]
+fn history_records_parse_back_into_plates() {
+    let history: PlateHistory = serde_json::from_value(json!({ "patente": "ab-123-cd" })).unwrap();
+    let input: Plate = "AB 123 CD".parse().unwrap();
+    assert_eq!(history.plate(), Some(input));
+
+    let missing: PlateHistory = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(missing.plate(), None);
+}
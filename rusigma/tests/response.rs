@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+use rusigma::response::{
+    DNIProfesional, DNIStandardResponse, MovistarEmail, PhoneRecord, PlateHistory, TitularCBU,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Decodes a Sigma body, encodes it again and checks nothing changed
+fn round_trip<T>(body: Value) -> T
+where
+    T: Serialize + DeserializeOwned + Clone + PartialEq + std::fmt::Debug,
+{
+    let decoded: T = serde_json::from_value(body).unwrap();
+    let encoded = serde_json::to_value(&decoded).unwrap();
+    let decoded_again: T = serde_json::from_value(encoded).unwrap();
+    assert_eq!(decoded_again, decoded.clone());
+    decoded
+}
+
+#[test]
+fn responses_round_trip() {
+    round_trip::<DNIStandardResponse>(dni_standard());
+    round_trip::<Vec<PhoneRecord>>(phone_numbers());
+    round_trip::<Vec<PlateHistory>>(plate_history());
+    round_trip::<DNIProfesional>(dni_profesional());
+    round_trip::<MovistarEmail>(movistar_email());
+    round_trip::<TitularCBU>(titular_cbu());
+}
+
+#[test]
+fn serialized_names_are_the_wire_names() {
+    let standard: DNIStandardResponse = serde_json::from_value(dni_standard()).unwrap();
+    assert_eq!(serde_json::to_value(&standard).unwrap()["doc"], "30123456");
+
+    let movistar: MovistarEmail = serde_json::from_value(movistar_email()).unwrap();
+    let encoded = serde_json::to_value(&movistar).unwrap();
+    assert!(encoded.get("num").is_some());
+    assert!(encoded.get("numero").is_none());
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn every_response_type_has_a_schema() {
+    let schemas = rusigma::response::schemas();
+    assert_eq!(schemas.len(), 15);
+
+    let standard = serde_json::to_value(&schemas["DNIStandardResponse"]).unwrap();
+    let properties = standard["properties"].as_object().unwrap();
+    assert!(properties.contains_key("doc"));
+    assert!(!properties.contains_key("documento"));
+    let required: Vec<_> = standard["required"].as_array().unwrap().iter().collect();
+    assert!(required.contains(&&Value::from("apellido")));
+    assert!(!required.contains(&&Value::from("codigo_postal")));
+}
@@ -0,0 +1,211 @@
+mod common;
+
+use common::*;
+use rusigma::retry::{is_retryable, RetryPolicy};
+use rusigma::{Error, SigmaClient};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .initial_backoff(Duration::from_millis(10))
+        .max_backoff(Duration::from_millis(50))
+        .jitter(false)
+}
+
+async fn client_with(policy: RetryPolicy) -> (MockServer, SigmaClient) {
+    setup_with(PLAN_STANDARD, |builder| builder.retry_policy(policy)).await
+}
+
+async fn fail_times(server: &MockServer, times: u64, response: ResponseTemplate) {
+    Mock::given(method("POST"))
+        .respond_with(response)
+        .up_to_n_times(times)
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+async fn then_succeed(server: &MockServer) {
+    Mock::given(method("POST"))
+        .respond_with(ok(dni_standard()))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn retries_server_errors_until_success() {
+    let retries = Arc::new(AtomicU32::new(0));
+    let counter = retries.clone();
+    let (server, client) = client_with(fast_policy().on_retry(move |attempt| {
+        assert!(matches!(
+            attempt.error,
+            Error::ServerError { status: 503, .. }
+        ));
+        counter.store(attempt.attempt, Ordering::SeqCst);
+    }))
+    .await;
+    fail_times(&server, 2, ResponseTemplate::new(503)).await;
+    then_succeed(&server).await;
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(result.is_ok());
+    assert_eq!(retries.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let (server, client) = client_with(fast_policy().max_attempts(2)).await;
+    fail_times(&server, 2, ResponseTemplate::new(502)).await;
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(
+        result,
+        Err(Error::ServerError { status: 502, .. })
+    ));
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let (server, client) = client_with(fast_policy()).await;
+    fail_times(&server, 1, api_error(404, "Sin resultados")).await;
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::NotFound { .. })));
+}
+
+#[tokio::test]
+async fn no_policy_means_no_retry() {
+    let (server, client) = setup(PLAN_STANDARD).await;
+    fail_times(&server, 1, ResponseTemplate::new(503)).await;
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::ServerError { .. })));
+}
+
+#[tokio::test]
+async fn rate_limits_wait_for_retry_after() {
+    let delays = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = delays.clone();
+    let (server, client) = client_with(
+        fast_policy()
+            .max_backoff(Duration::from_secs(2))
+            .on_retry(move |attempt| recorded.lock().unwrap().push(attempt.delay)),
+    )
+    .await;
+    fail_times(
+        &server,
+        1,
+        ResponseTemplate::new(429).insert_header("Retry-After", "1"),
+    )
+    .await;
+    then_succeed(&server).await;
+
+    client.search_standard_dni(dni()).await.unwrap();
+    assert_eq!(*delays.lock().unwrap(), vec![Duration::from_secs(1)]);
+}
+
+#[tokio::test]
+async fn long_retry_after_is_not_waited() {
+    let (server, client) = client_with(fast_policy()).await;
+    fail_times(
+        &server,
+        1,
+        ResponseTemplate::new(429).insert_header("Retry-After", "3600"),
+    )
+    .await;
+
+    let result = client.search_standard_dni(dni()).await;
+    assert!(matches!(result, Err(Error::RateLimited { .. })));
+}
+
+#[tokio::test]
+async fn timeouts_are_retried() {
+    let (server, client) = setup_with(PLAN_STANDARD, |builder| {
+        builder
+            .timeout(Duration::from_millis(100))
+            .retry_policy(fast_policy())
+    })
+    .await;
+    fail_times(
+        &server,
+        1,
+        ok(dni_standard()).set_delay(Duration::from_secs(1)),
+    )
+    .await;
+    then_succeed(&server).await;
+
+    assert!(client.search_standard_dni(dni()).await.is_ok());
+}
+
+#[test]
+fn backoff_grows_exponentially_up_to_the_maximum() {
+    let policy = RetryPolicy::new()
+        .max_attempts(10)
+        .initial_backoff(Duration::from_millis(100))
+        .max_backoff(Duration::from_millis(350))
+        .jitter(false);
+    let error = Error::Timeout;
+    assert_eq!(
+        policy.delay_for(2, &error),
+        Some(Duration::from_millis(100))
+    );
+    assert_eq!(
+        policy.delay_for(3, &error),
+        Some(Duration::from_millis(200))
+    );
+    assert_eq!(
+        policy.delay_for(4, &error),
+        Some(Duration::from_millis(350))
+    );
+    assert_eq!(policy.delay_for(11, &error), None);
+}
+
+#[test]
+fn jitter_stays_within_half_and_full_delay() {
+    let policy = RetryPolicy::new().initial_backoff(Duration::from_millis(100));
+    for _ in 0..100 {
+        let delay = policy.delay_for(2, &Error::Timeout).unwrap();
+        assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn only_transient_errors_are_retryable() {
+    assert!(is_retryable(&Error::Timeout));
+    assert!(is_retryable(&Error::Connection("reset".to_string())));
+    assert!(!is_retryable(&Error::HTTPInternalError(
+        "builder error".to_string()
+    )));
+    assert!(is_retryable(&Error::ServerError {
+        status: 500,
+        message: String::new()
+    }));
+    assert!(!is_retryable(&Error::ServerError {
+        status: 501,
+        message: String::new()
+    }));
+    assert!(!is_retryable(&Error::NoLoginToken));
+    assert!(!is_retryable(&Error::Unauthorized {
+        status: 401,
+        message: String::new()
+    }));
+}
+
+#[tokio::test]
+async fn only_connection_failures_of_reqwest_are_retried() {
+    let http = reqwest::Client::new();
+    let refused = http.post("http://127.0.0.1:1/").send().await.unwrap_err();
+    let error = Error::from(refused);
+    assert!(matches!(error, Error::Connection(_)), "{:?}", error);
+    assert!(is_retryable(&error));
+
+    let invalid = http.post("not a url").send().await.unwrap_err();
+    let error = Error::from(invalid);
+    assert!(matches!(error, Error::HTTPInternalError(_)), "{:?}", error);
+    assert!(!is_retryable(&error));
+}
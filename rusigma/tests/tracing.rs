@@ -0,0 +1,144 @@
+mod common;
+
+use common::*;
+use rusigma::retry::RetryPolicy;
+use rusigma::SigmaClient;
+use serde_json::json;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::subscriber::DefaultGuard;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const DNI_PATH: &str = "/api/v2/standard/osint/argentina/resolver/dni";
+
+/// Formatted trace output, written by the subscriber of the current thread
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    fn start() -> (Captured, DefaultGuard) {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_span_events(FmtSpan::CLOSE)
+            .with_max_level(tracing::Level::DEBUG)
+            .finish();
+        (captured, tracing::subscriber::set_default(subscriber))
+    }
+
+    fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Captured {
+    type Writer = Captured;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+async fn traced_client(log_sensitive_data: bool) -> (MockServer, SigmaClient) {
+    setup_with(PLAN_STANDARD, |builder| {
+        builder
+            .retry_policy(
+                RetryPolicy::new()
+                    .initial_backoff(Duration::from_millis(10))
+                    .jitter(false),
+            )
+            .log_sensitive_data(log_sensitive_data)
+    })
+    .await
+}
+
+#[tokio::test]
+async fn queries_are_traced_without_personal_data() -> anyhow::Result<()> {
+    let (server, client) = traced_client(false).await;
+    Mock::given(method("POST"))
+        .and(path(DNI_PATH))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(DNI_PATH))
+        .respond_with(ok(dni_standard()))
+        .mount(&server)
+        .await;
+    let (captured, _guard) = Captured::start();
+
+    client.search_standard_dni(dni()).await?;
+    let output = captured.output();
+    for field in [
+        "sigma.request",
+        "endpoint=\"standard_dni\"",
+        "plan=\"standard\"",
+        "status=200",
+        "retries=1",
+        "latency_ms=",
+        "retrying sigma query",
+    ] {
+        assert!(output.contains(field), "{} missing in {}", field, output);
+    }
+    assert!(output.contains("sigma_key=[redacted]"));
+    assert!(!output.contains(TOKEN));
+    assert!(!output.contains("30123456"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn sensitive_data_is_logged_when_enabled() -> anyhow::Result<()> {
+    let (server, client) = traced_client(true).await;
+    Mock::given(method("POST"))
+        .and(path(DNI_PATH))
+        .respond_with(api_error(404, "No hay datos del DNI 30123456"))
+        .mount(&server)
+        .await;
+    let (captured, _guard) = Captured::start();
+
+    assert!(client.search_standard_dni(dni()).await.is_err());
+    let output = captured.output();
+    assert!(output.contains(&format!("sigma_key={}", TOKEN)));
+    assert!(output.contains("\"dni\":\"30123456\""));
+    assert!(output.contains("sigma query failed"));
+    assert!(output.contains("No hay datos del DNI 30123456"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn login_is_traced_without_the_password() -> anyhow::Result<()> {
+    let (server, client) = traced_client(false).await;
+    Mock::given(method("POST"))
+        .and(path("/api/sigma/client/login"))
+        .respond_with(ok(json!({ "token": "fresh-token", "plan": 2 })))
+        .mount(&server)
+        .await;
+    let (captured, _guard) = Captured::start();
+
+    client
+        .login_with_credentials("juan".to_string(), "secreto".to_string())
+        .await?;
+    let output = captured.output();
+    assert!(output.contains("sigma.login"));
+    assert!(output.contains("status=200"));
+    assert!(output.contains("plan=\"medium\""));
+    assert!(!output.contains("secreto"));
+    assert!(!output.contains("juan"));
+    Ok(())
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use async_trait::async_trait;
+use common::*;
+use rusigma::errors::Error;
+use rusigma::transport::{Method, Transport, TransportRequest, TransportResponse};
+use rusigma::{Plan, SigmaClient};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+/// Answers every request with a fixed response and records what was sent
+#[derive(Debug, Clone)]
+struct RecordingTransport {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    requests: Arc<Mutex<Vec<TransportRequest>>>,
+}
+
+impl RecordingTransport {
+    fn new(status: u16, body: serde_json::Value) -> RecordingTransport {
+        RecordingTransport {
+            status,
+            headers: Vec::new(),
+            body: body.to_string().into_bytes(),
+            requests: Arc::default(),
+        }
+    }
+
+    fn requests(&self) -> Vec<TransportRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, Error> {
+        self.requests.lock().unwrap().push(request);
+        Ok(TransportResponse {
+            status: self.status,
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+        })
+    }
+}
+
+/// Middleware counting the requests going through the wrapped transport
+#[derive(Debug)]
+struct Counting<T> {
+    inner: T,
+    count: Arc<Mutex<u32>>,
+}
+
+#[async_trait]
+impl<T: Transport> Transport for Counting<T> {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, Error> {
+        *self.count.lock().unwrap() += 1;
+        self.inner.send(request).await
+    }
+}
+
+fn client_with(transport: impl Transport + 'static, plan: Plan) -> SigmaClient {
+    client(plan, |builder| {
+        builder.base_url("http://sigma.test").transport(transport)
+    })
+}
+
+#[tokio::test]
+async fn queries_go_through_the_injected_transport() {
+    let transport = RecordingTransport::new(200, dni_standard());
+    let client = client_with(transport.clone(), PLAN_STANDARD);
+
+    let result = client.search_standard_dni(dni()).await.unwrap();
+    assert_eq!(result.documento, "30123456");
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(request.method, Method::Post);
+    assert_eq!(
+        request.url,
+        "http://sigma.test/api/v2/standard/osint/argentina/resolver/dni"
+    );
+    assert_eq!(request.header("Sigma-Key"), Some(TOKEN));
+    assert_eq!(request.body, Some(json!({ "dni": "30123456" })));
+}
+
+#[tokio::test]
+async fn login_goes_through_the_injected_transport() {
+    let transport = RecordingTransport::new(200, json!({ "token": "new-token", "plan": 1 }));
+    let client = SigmaClient::builder()
+        .base_url("http://sigma.test")
+        .transport(transport.clone())
+        .build()
+        .unwrap();
+
+    client
+        .login_with_credentials("user".to_string(), "secret".to_string())
+        .await
+        .unwrap();
+    assert_eq!(client.http.token().as_deref(), Some("new-token"));
+    assert_eq!(client.http.plan(), Some(Plan::Profesional));
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].url, "http://sigma.test/api/sigma/client/login");
+    assert_eq!(requests[0].header("sigma-key"), None);
+}
+
+#[tokio::test]
+async fn error_statuses_from_the_transport_are_mapped() {
+    let mut transport =
+        RecordingTransport::new(429, json!({ "error": true, "mensaje": "despacio" }));
+    transport.headers = vec![("Retry-After".to_string(), "7".to_string())];
+    let client = client_with(transport, PLAN_STANDARD);
+
+    let error = client.search_standard_dni(dni()).await.unwrap_err();
+    match error {
+        Error::RateLimited {
+            retry_after,
+            message,
+        } => {
+            assert_eq!(retry_after, Some(std::time::Duration::from_secs(7)));
+            assert_eq!(message, "despacio");
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn transports_can_be_wrapped() {
+    let count = Arc::new(Mutex::new(0));
+    let transport = Counting {
+        inner: RecordingTransport::new(200, dni_standard()),
+        count: count.clone(),
+    };
+    let client = client_with(transport, PLAN_STANDARD);
+
+    for _ in 0..3 {
+        client.search_standard_dni(dni()).await.unwrap();
+    }
+    assert_eq!(*count.lock().unwrap(), 3);
+}
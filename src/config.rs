@@ -0,0 +1,271 @@
+extern crate rusigma;
+use rusigma::client::SigmaClient;
+use rusigma::Plan;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Version del formato de `config.toml` que escribe esta version de sigma-cli
+pub const CONFIG_VERSION: u32 = 1;
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Configuracion en `$XDG_CONFIG_HOME/sigma/config.toml`
+///
+/// ```toml
+/// version = 1
+/// default_profile = "default"
+///
+/// [profiles.default]
+/// token = "..."
+/// plan = "medium"
+/// output = "json"
+///
+/// [profiles.default.limits]
+/// standard_dni = 100
+/// por_minuto = 30
+/// ```
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Config {
+    pub version: u32,
+    #[serde(default = "default_profile")]
+    pub default_profile: String,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            version: CONFIG_VERSION,
+            default_profile: default_profile(),
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+/// Cuenta de Sigma y preferencias con las que se hacen las consultas
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Token cifrada con una frase de paso, en lugar de `token`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<Plan>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputFormat>,
+    /// Mismas claves que `~/.sigma.limits`, que se usa si esta vacio
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub limits: BTreeMap<String, u32>,
+}
+
+#[derive(clap::ValueEnum, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Tabla,
+    Json,
+}
+
+/// Solo la version, leida antes que el resto para que un formato nuevo de un error claro
+#[derive(Deserialize)]
+struct Versioned {
+    version: Option<u32>,
+}
+
+fn default_profile() -> String {
+    String::from(DEFAULT_PROFILE)
+}
+
+pub fn config_path() -> PathBuf {
+    let config_home = match env::var_os("XDG_CONFIG_HOME").map(PathBuf::from) {
+        Some(path) if path.is_absolute() => path,
+        _ => match home::home_dir() {
+            Some(path) => path.join(".config"),
+            None => panic!("Impossible to get your home directory"),
+        },
+    };
+    config_home.join("sigma").join("config.toml")
+}
+
+/// Los nombres de perfil se usan en rutas, como el directorio del cache
+pub fn check_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("el nombre de perfil {:?} no es valido", name));
+    }
+    Ok(())
+}
+
+/// Archivo `token:plan` que usaban las versiones anteriores
+fn legacy_path() -> PathBuf {
+    match home::home_dir() {
+        Some(path) => path.join(".sigma.conf"),
+        None => panic!("Impossible to get your home directory"),
+    }
+}
+
+/// Copia de `~/.sigma.conf` que dejaba la migracion de la version anterior
+fn legacy_backup_path() -> PathBuf {
+    legacy_path().with_extension("conf.old")
+}
+
+pub fn parse(content: &str) -> Result<Config, String> {
+    let versioned: Versioned = toml::from_str(content).map_err(|e| e.to_string())?;
+    match versioned.version {
+        Some(CONFIG_VERSION) => toml::from_str(content).map_err(|e| e.to_string()),
+        Some(version) => Err(format!(
+            "version {} no soportada, esta version de sigma-cli usa la version {}",
+            version, CONFIG_VERSION
+        )),
+        None => Err(String::from("falta la clave version")),
+    }
+}
+
+/// Lee la configuracion, migrando `~/.sigma.conf` si todavia no existe
+pub fn load() -> Result<Config, String> {
+    let path = config_path();
+    match check_permissions(&path).and_then(|_| fs::read_to_string(&path)) {
+        Ok(content) => parse(&content).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => migrate(&path, &legacy_path()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+pub fn save(config: &Config) -> Result<(), String> {
+    write(&config_path(), config)
+}
+
+/// Escribe un archivo temporal que solo lee el usuario y lo renombra sobre
+/// `path`, asi una escritura fallida nunca deja la configuracion a medias.
+/// El contenido anterior se borra despues, desde un link al archivo viejo.
+fn write(path: &Path, config: &Config) -> Result<(), String> {
+    let content = toml::to_string_pretty(config).map_err(|e| e.to_string())?;
+    if let Some(dir) = path.parent() {
+        create_private_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let temporary = path.with_extension(format!("toml.{}.tmp", process::id()));
+    let previous = path.with_extension(format!("toml.{}.old", process::id()));
+    let _ = fs::remove_file(&temporary);
+    let _ = fs::remove_file(&previous);
+    let linked = fs::hard_link(path, &previous).is_ok();
+    let result =
+        write_private(&temporary, content.as_bytes()).and_then(|_| fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+        let _ = fs::remove_file(&previous);
+    } else if linked {
+        wipe(&previous)?;
+    }
+    result.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_private(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    file.write_all(data)?;
+    file.sync_all()
+}
+
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+/// Llena `path` de ceros, para no dejar tokens viejas en los bloques del archivo
+fn overwrite(path: &Path) -> io::Result<()> {
+    let len = match fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    file.write_all(&vec![0u8; len as usize])?;
+    file.sync_all()
+}
+
+/// Sobreescribe y borra `path`, si existe
+fn wipe(path: &Path) -> Result<(), String> {
+    overwrite(path)
+        .and_then(|_| fs::remove_file(path))
+        .or_else(|e| match e.kind() {
+            io::ErrorKind::NotFound => Ok(()),
+            _ => Err(format!("{}: {}", path.display(), e)),
+        })
+}
+
+/// Borra `~/.sigma.conf` y la copia que dejaban versiones anteriores
+pub fn wipe_legacy() -> Result<(), String> {
+    wipe(&legacy_path())?;
+    wipe(&legacy_backup_path())
+}
+
+/// Rechaza archivos que otros usuarios pueden leer o modificar, guardan la token
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "otros usuarios pueden leer este archivo, corre chmod 600 {}",
+                path.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Pasa el token y plan de `legacy` al perfil por defecto y borra el archivo
+fn migrate(path: &Path, legacy: &Path) -> Result<Config, String> {
+    let credentials = match fs::read_to_string(legacy) {
+        Ok(credentials) => credentials,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(format!("{}: {}", legacy.display(), e)),
+    };
+    let exposed = check_permissions(legacy).is_err();
+    let session = SigmaClient::from_creds(credentials)
+        .map_err(|e| format!("{}: {}", legacy.display(), e))?
+        .http
+        .session();
+    let mut config = Config::default();
+    config.profiles.insert(
+        default_profile(),
+        Profile {
+            token: session.token,
+            plan: session.plan,
+            ..Profile::default()
+        },
+    );
+    write(path, &config)?;
+    wipe(legacy)?;
+    eprintln!(
+        "Tu token se movio de {} al perfil {} de {}",
+        legacy.display(),
+        DEFAULT_PROFILE,
+        path.display()
+    );
+    if exposed {
+        eprintln!("Aviso: otros usuarios podian leer tu token, renovala con sigma-cli login");
+    }
+    Ok(config)
+}
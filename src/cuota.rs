@@ -0,0 +1,106 @@
+extern crate rusigma;
+use rusigma::endpoints::Endpoint;
+use rusigma::limits::{QueryBudget, RateLimiter};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Limites locales leidos de `~/.sigma.limits` o de la tabla `limits` del perfil
+///
+/// Cada linea tiene la forma `clave = valor`, donde la clave es el nombre de
+/// un endpoint (`standard_dni`, `plate`, ...), `default` para el resto de los
+/// endpoints o `por_minuto` para limitar la velocidad de las consultas.
+#[derive(Debug, Default)]
+pub struct Limits {
+    pub per_endpoint: Vec<(Endpoint, u32)>,
+    pub default: Option<u32>,
+    pub per_minute: Option<u32>,
+}
+
+fn home_file(name: &str) -> String {
+    let config_home = match home::home_dir() {
+        Some(path) => path.into_os_string().into_string().unwrap(),
+        None => panic!("Impossible to get your home directory"),
+    };
+    format!("{}/{}", config_home, name)
+}
+
+pub fn limits_path() -> String {
+    home_file(".sigma.limits")
+}
+
+/// Contadores de un perfil, junto a `config.toml`, asi cada cuenta se
+/// compara solo con sus propias consultas
+pub fn usage_path(profile: &str) -> PathBuf {
+    crate::config::config_path().with_file_name(format!("usage-{}.json", profile))
+}
+
+pub fn parse_limits(content: &str) -> Result<Limits, String> {
+    let mut limits = Limits::default();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(format!("linea {}: se esperaba clave = valor", number + 1))?;
+        let value = value
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| format!("linea {}: {} no es un numero", number + 1, value.trim()))?;
+        set_limit(&mut limits, key.trim(), value)
+            .map_err(|e| format!("linea {}: {}", number + 1, e))?;
+    }
+    Ok(limits)
+}
+
+/// Limites de la tabla `limits` de un perfil, con las claves de `~/.sigma.limits`
+pub fn profile_limits(entries: &BTreeMap<String, u32>) -> Result<Limits, String> {
+    let mut limits = Limits::default();
+    for (key, value) in entries {
+        set_limit(&mut limits, key, *value)?;
+    }
+    Ok(limits)
+}
+
+fn set_limit(limits: &mut Limits, key: &str, value: u32) -> Result<(), String> {
+    match key {
+        "default" => limits.default = Some(value),
+        "por_minuto" => limits.per_minute = Some(value),
+        name => {
+            let endpoint = name
+                .parse::<Endpoint>()
+                .map_err(|_| format!("endpoint desconocido {}", name))?;
+            limits.per_endpoint.push((endpoint, value));
+        }
+    }
+    Ok(())
+}
+
+/// Limites de `~/.sigma.limits`, ninguno si el archivo no existe
+pub fn read_limits() -> Result<Limits, String> {
+    match fs::read_to_string(limits_path()) {
+        Ok(content) => parse_limits(&content).map_err(|e| format!("{}: {}", limits_path(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Limits::default()),
+        Err(e) => Err(format!("{}: {}", limits_path(), e)),
+    }
+}
+
+/// Presupuesto diario con los contadores del perfil persistidos en `usage_path`
+pub fn query_budget(limits: &Limits, profile: &str) -> Result<QueryBudget, String> {
+    let mut budget = QueryBudget::new();
+    for (endpoint, limit) in &limits.per_endpoint {
+        budget = budget.limit(*endpoint, *limit);
+    }
+    if let Some(default) = limits.default {
+        budget = budget.default_limit(default);
+    }
+    budget
+        .persist_to(usage_path(profile))
+        .map_err(|e| e.to_string())
+}
+
+pub fn rate_limiter(limits: &Limits) -> Option<RateLimiter> {
+    limits.per_minute.map(RateLimiter::per_minute)
+}
@@ -1,53 +1,396 @@
 extern crate rusigma;
 extern crate tabled;
+use crate::config::{self, Config, OutputFormat, Profile};
+use crate::cuota;
+use crate::secret;
+use async_trait::async_trait;
+use rusigma::builder::SigmaClientBuilder;
+use rusigma::cache::{self, ResponseCache};
+use rusigma::cbu::CbuOrAlias;
 use rusigma::client::SigmaClient;
-use std::collections::HashMap;
-use std::fs;
+use rusigma::credentials::{
+    Credentials, CredentialsProvider, DEFAULT_PASSWORD_VAR, DEFAULT_USERNAME_VAR,
+};
+use rusigma::decode::DecodeMode;
+use rusigma::document::{Dni, Gender};
+use rusigma::filters::NameSearchFilters;
+use rusigma::http::Session;
+use rusigma::limits::QueryBudget;
+use rusigma::parsed::{NaiveDate, Parsed};
+use rusigma::phone::PhoneNumber;
+use rusigma::plate::Plate;
+use rusigma::response::PlateHistory;
+use rusigma::Error;
+use serde::Serialize;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use tabled::{builder::Builder, Style};
 
-pub async fn handle_login(username: &String, password: &String) {
-    let config_home = match home::home_dir() {
-        Some(path) => path.into_os_string().into_string().unwrap(),
-        None => panic!("Impossible to get your home directory"),
-    };
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+static NO_CACHE: AtomicBool = AtomicBool::new(false);
+static REFRESH_CACHE: AtomicBool = AtomicBool::new(false);
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Frase de paso del cache de respuestas, sin ella el cache no se usa
+const CACHE_PASSPHRASE_VAR: &str = "SIGMA_CACHE_PASSPHRASE";
+
+/// Muestra en stderr los avisos de decodificacion de cada respuesta
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// `--no-cache` y `--refresh`
+pub fn set_cache_options(no_cache: bool, refresh: bool) {
+    NO_CACHE.store(no_cache, Ordering::Relaxed);
+    REFRESH_CACHE.store(refresh, Ordering::Relaxed);
+}
+
+/// `--profile` y `--formato`, por defecto el formato del perfil
+pub fn set_profile(profile: Option<String>, format: Option<OutputFormat>) {
+    let config = load_config();
+    let name = profile.unwrap_or_else(|| config.default_profile.clone());
+    if let Err(e) = config::check_profile_name(&name) {
+        exit_with_error(&e);
+    }
+    let format = format
+        .or_else(|| config.profiles.get(&name).and_then(|p| p.output))
+        .unwrap_or_default();
+    JSON_OUTPUT.store(format == OutputFormat::Json, Ordering::Relaxed);
+    let _ = PROFILE.set(name);
+}
+
+fn profile_name() -> &'static str {
+    PROFILE
+        .get()
+        .map(String::as_str)
+        .unwrap_or(config::DEFAULT_PROFILE)
+}
+
+/// Muestra el error en stderr y termina con codigo 1
+pub fn exit_with_error(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    process::exit(1)
+}
+
+/// Configuracion, migrando `~/.sigma.conf` la primera vez
+fn load_config() -> Config {
+    config::load().unwrap_or_else(|e| exit_with_error(&e))
+}
+
+/// Perfil elegido, vacio si todavia no existe
+fn selected_profile() -> Profile {
+    load_config()
+        .profiles
+        .remove(profile_name())
+        .unwrap_or_default()
+}
 
-    let mut sclient = SigmaClient::new();
+/// Decodifica en modo lenient, asi un campo inconsistente se muestra vacio
+/// en lugar de hacer fallar la consulta
+fn client_builder(profile: &Profile) -> SigmaClientBuilder {
+    let builder = SigmaClient::builder().decode_mode(DecodeMode::Lenient);
+    match &profile.base_url {
+        Some(base_url) => builder.base_url(base_url),
+        None => builder,
+    }
+}
+
+/// Frase de paso de la token cifrada, de SIGMA_TOKEN_PASSPHRASE o pedida una
+/// vez en la terminal, dos si es nueva
+fn token_passphrase(new: bool) -> String {
+    static PASSPHRASE: OnceLock<String> = OnceLock::new();
+    PASSPHRASE
+        .get_or_init(|| ask_passphrase(new).unwrap_or_else(|e| exit_with_error(&e)))
+        .clone()
+}
+
+fn ask_passphrase(new: bool) -> Result<String, String> {
+    if let Ok(passphrase) = env::var(secret::PASSPHRASE_VAR) {
+        return Ok(passphrase);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(format!(
+            "la token esta cifrada y {} no esta definida",
+            secret::PASSPHRASE_VAR
+        ));
+    }
+    let passphrase =
+        rpassword::prompt_password("Frase de paso de la token: ").map_err(|e| e.to_string())?;
+    if passphrase.is_empty() {
+        return Err(String::from("la frase de paso no puede estar vacia"));
+    }
+    if new
+        && rpassword::prompt_password("Repeti la frase de paso: ").map_err(|e| e.to_string())?
+            != passphrase
+    {
+        return Err(String::from("las frases de paso no coinciden"));
+    }
+    Ok(passphrase)
+}
+
+/// Token del perfil, descifrada si hace falta
+fn profile_token(profile: &Profile) -> Option<String> {
+    match (&profile.token, &profile.encrypted_token) {
+        (Some(token), _) => Some(token.clone()),
+        (None, Some(sealed)) => Some(
+            secret::decrypt(sealed, &token_passphrase(false))
+                .unwrap_or_else(|e| exit_with_error(&e)),
+        ),
+        (None, None) => None,
+    }
+}
+
+pub async fn handle_login(username: &String, password: &String, encrypt: bool) {
+    let sclient = client_builder(&selected_profile())
+        .build()
+        .unwrap_or_else(|e| exit_with_error(&e.to_string()));
     sclient
         .login_with_credentials(username.to_string(), password.to_string())
         .await
         .expect("Unable to log-in with the given credentials");
 
-    let credentials = format!(
-        "{}:{}",
-        sclient.http.token_str.unwrap(),
-        sclient.http.plan_id.unwrap(),
-    );
-    fs::write(format!("{}/.sigma.conf", config_home), credentials).expect("Could not save token");
+    save_token(&sclient.http.session(), encrypt);
     println!(
-        "Estas logueado y tu token ha sido guardada en {}/.sigma.conf",
-        config_home
+        "Estas logueado y tu token ha sido guardada en el perfil {} de {}",
+        profile_name(),
+        config::config_path().display()
     );
 }
 
-pub fn read_credentials() -> Result<String, String> {
+/// Guarda la token y el plan en el perfil elegido, la token sigue cifrada si
+/// ya lo estaba
+fn save_token(session: &Session, encrypt: bool) {
+    let mut config = load_config();
+    let profile = config
+        .profiles
+        .entry(profile_name().to_string())
+        .or_default();
+    if encrypt || profile.encrypted_token.is_some() {
+        profile.encrypted_token = session.token.as_deref().map(|token| {
+            secret::encrypt(token, &token_passphrase(true)).unwrap_or_else(|e| exit_with_error(&e))
+        });
+        profile.token = None;
+    } else {
+        profile.token = session.token.clone();
+    }
+    profile.plan = session.plan;
+    if let Err(e) = config::save(&config) {
+        exit_with_error(&e);
+    }
+}
+
+pub fn handle_logout() {
+    let mut config = load_config();
+    let logged_in = match config.profiles.get_mut(profile_name()) {
+        Some(profile) => {
+            let logged_in = profile.token.is_some() || profile.encrypted_token.is_some();
+            profile.token = None;
+            profile.encrypted_token = None;
+            profile.plan = None;
+            logged_in
+        }
+        None => false,
+    };
+    if let Err(e) = config::save(&config).and_then(|_| config::wipe_legacy()) {
+        exit_with_error(&e);
+    }
+    if logged_in {
+        println!("Se borro la token del perfil {}", profile_name());
+    } else {
+        println!("El perfil {} no tenia una token guardada", profile_name());
+    }
+}
+
+/// Credenciales para renovar una token vencida, de SIGMA_USERNAME y
+/// SIGMA_PASSWORD o pedidas en la terminal
+fn ask_credentials() -> Result<Credentials, Error> {
+    if let (Ok(username), Ok(password)) = (
+        env::var(DEFAULT_USERNAME_VAR),
+        env::var(DEFAULT_PASSWORD_VAR),
+    ) {
+        return Ok(Credentials::new(username, password));
+    }
+    if !io::stdin().is_terminal() {
+        return Err(Error::CredentialsUnavailable(String::from(
+            "SIGMA_USERNAME y SIGMA_PASSWORD no estan definidas",
+        )));
+    }
+    eprintln!("Tu token vencio, ingresa tus credenciales para renovarla");
+    eprint!("Usuario: ");
+    let mut username = String::new();
+    io::stdin()
+        .read_line(&mut username)
+        .map_err(|e| Error::CredentialsUnavailable(e.to_string()))?;
+    let password = rpassword::prompt_password("Contraseña: ")
+        .map_err(|e| Error::CredentialsUnavailable(e.to_string()))?;
+    Ok(Credentials::new(username.trim(), password))
+}
+
+/// Pide las credenciales en un hilo aparte, leer la terminal bloquea y no
+/// debe frenar el runtime mientras se espera al usuario
+struct TerminalCredentials;
+
+#[async_trait]
+impl CredentialsProvider for TerminalCredentials {
+    async fn credentials(&self) -> Result<Credentials, Error> {
+        tokio::task::spawn_blocking(ask_credentials)
+            .await
+            .map_err(|e| Error::CredentialsUnavailable(e.to_string()))?
+    }
+}
+
+fn cache_path() -> String {
     let config_home = match home::home_dir() {
         Some(path) => path.into_os_string().into_string().unwrap(),
         None => panic!("Impossible to get your home directory"),
     };
-    Ok(fs::read_to_string(format!("{}/.sigma.conf", config_home)).expect("Could not read token"))
+    format!("{}/.sigma.cache", config_home)
+}
+
+/// Cache en `~/.sigma.cache/<perfil>`, salvo que este desactivado o sin frase
+/// de paso. Cada perfil tiene el suyo, asi una cuenta nunca lee las
+/// respuestas de otra.
+fn open_cache() -> Option<ResponseCache> {
+    if NO_CACHE.load(Ordering::Relaxed) {
+        return None;
+    }
+    let passphrase = env::var(CACHE_PASSPHRASE_VAR).ok()?;
+    let dir = std::path::Path::new(&cache_path()).join(profile_name());
+    match ResponseCache::open(dir, &passphrase) {
+        Ok(cache) => Some(cache.refresh(REFRESH_CACHE.load(Ordering::Relaxed))),
+        Err(e) => {
+            eprintln!("Aviso: no se usa el cache: {}", e);
+            None
+        }
+    }
+}
+
+/// Limites del perfil, o los de `~/.sigma.limits` si no tiene
+fn read_limits(profile: &Profile) -> cuota::Limits {
+    if profile.limits.is_empty() {
+        return cuota::read_limits().unwrap_or_else(|e| exit_with_error(&e));
+    }
+    cuota::profile_limits(&profile.limits).unwrap_or_else(|e| {
+        exit_with_error(&format!("limites del perfil {}: {}", profile_name(), e))
+    })
+}
+
+fn query_budget(limits: &cuota::Limits) -> QueryBudget {
+    cuota::query_budget(limits, profile_name()).unwrap_or_else(|e| exit_with_error(&e))
+}
+
+/// Cliente con la token, plan, URL y limites del perfil elegido
+fn new_client() -> SigmaClient {
+    let profile = selected_profile();
+    let token = match profile_token(&profile) {
+        Some(token) => token,
+        None => exit_with_error(&format!(
+            "el perfil {} no tiene token, usa sigma-cli login --profile {}",
+            profile_name(),
+            profile_name()
+        )),
+    };
+    let limits = read_limits(&profile);
+    let mut client = client_builder(&profile)
+        .build()
+        .unwrap_or_else(|e| exit_with_error(&e.to_string()));
+    client.http.set_session(Session {
+        token: Some(token),
+        plan: profile.plan,
+    });
+    client
+        .http
+        .set_query_budget(Some(Arc::new(query_budget(&limits))));
+    client
+        .http
+        .set_rate_limiter(cuota::rate_limiter(&limits).map(Arc::new));
+    client
+        .http
+        .set_credentials_provider(Some(Arc::new(TerminalCredentials)));
+    client.http.set_token_refreshed_hook(|session| {
+        save_token(session, false);
+        eprintln!("Tu token fue renovada y guardada");
+    });
+    client.http.set_cache(open_cache().map(Arc::new));
+    if VERBOSE.load(Ordering::Relaxed) {
+        client
+            .http
+            .set_warning_hook(|warning| eprintln!("Aviso: {}", warning));
+    }
+    client
 }
 
-pub async fn handle_buscar_dni(dni: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    match client.search_standard_dni(dni.to_string()).await {
+pub fn handle_cache_purge() {
+    match cache::purge(cache_path()) {
+        Ok(()) => println!("Se borro el cache de {}", cache_path()),
+        Err(e) => println!("Error: {}", e),
+    };
+}
+
+/// Muestra una respuesta como JSON, para `--formato json`
+fn print_json<T: Serialize>(response: &T) {
+    match serde_json::to_string_pretty(response) {
+        Ok(json) => println!("{}", json),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+pub fn handle_cuota() {
+    let profile = selected_profile();
+    let limits = read_limits(&profile);
+    let budget = query_budget(&limits);
+
+    let mut builder = Builder::default();
+    builder.set_columns(["Endpoint", "Usadas hoy", "Limite diario"]);
+    for usage in budget.report() {
+        builder.add_record([
+            usage.endpoint.to_string(),
+            usage.used.to_string(),
+            usage
+                .limit
+                .map(|limit| limit.to_string())
+                .unwrap_or_else(|| String::from("-")),
+        ]);
+    }
+
+    let table = builder.build().with(Style::rounded());
+    println!("Consultas del perfil {}", profile_name());
+    println!("{}", table);
+    if let Some(per_minute) = limits.per_minute {
+        println!("Limite de velocidad: {} consultas por minuto", per_minute);
+    }
+    if profile.limits.is_empty() {
+        println!("Limites configurados en {}", cuota::limits_path());
+    } else {
+        println!(
+            "Limites del perfil {} en {}",
+            profile_name(),
+            config::config_path().display()
+        );
+    }
+}
+
+pub async fn handle_buscar_dni(dni: &Dni) {
+    let client = new_client();
+    match client.search_standard_dni(dni.clone()).await {
+        Ok(v) if json_output() => print_json(&v),
         Ok(v) => println!("{:#?}", v),
         Err(e) => println!("Error: {}", e),
     };
 }
 
-pub async fn handle_buscar_celulares_dni(dni: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    match client.search_phones_by_dni(dni.to_string()).await {
+pub async fn handle_buscar_celulares_dni(dni: &Dni) {
+    let client = new_client();
+    match client.search_phones_by_dni(dni.clone()).await {
+        Ok(v) if json_output() => print_json(&v),
         Ok(v) => {
             let mut builder = Builder::default();
             builder.set_columns([
@@ -61,32 +404,47 @@ pub async fn handle_buscar_celulares_dni(dni: &String) {
             ]);
             for pn in v {
                 builder.add_record([
-                    pn.documento,
-                    pn.numero,
-                    pn.nombre,
-                    pn.localidad,
-                    pn.provincia,
-                    pn.codigo_postal,
-                    pn.empresa,
+                    pn.documento.unwrap_or(String::from("-")),
+                    pn.numero.unwrap_or(String::from("-")),
+                    pn.nombre.unwrap_or(String::from("-")),
+                    pn.localidad.unwrap_or(String::from("-")),
+                    pn.provincia.unwrap_or(String::from("-")),
+                    pn.codigo_postal.unwrap_or(String::from("-")),
+                    pn.empresa.unwrap_or(String::from("-")),
                 ]);
             }
 
             let table = builder.build().with(Style::rounded());
-            println!("{}", table.to_string());
+            println!("{}", table);
         }
         Err(e) => println!("Error: {}", e),
     };
 }
 
-pub async fn handle_buscar_patentes(input: &String, is_plate: bool) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    let response: Result<Vec<rusigma::response::PlateHistory>, rusigma::Error>;
-    if is_plate {
-        response = client.search_plate(input.to_string()).await;
-    } else {
-        response = client.search_plate_by_dni(input.to_string()).await;
+pub async fn handle_buscar_patentes(patente: &Plate) {
+    let client = new_client();
+    print_patentes(client.search_plate(patente.clone()).await);
+}
+
+pub async fn handle_buscar_patentes_dni(dni: &Dni) {
+    let client = new_client();
+    print_patentes(client.search_plate_by_dni(dni.clone()).await);
+}
+
+/// Fecha como dd/mm/aaaa, o el texto que mando Sigma si no es una fecha
+fn format_date(date: Option<Parsed<NaiveDate>>) -> String {
+    match date {
+        Some(date) => match date.get() {
+            Some(parsed) => parsed.format("%d/%m/%Y").to_string(),
+            None => date.to_string(),
+        },
+        None => String::default(),
     }
+}
+
+fn print_patentes(response: Result<Vec<PlateHistory>, Error>) {
     match response {
+        Ok(v) if json_output() => print_json(&v),
         Ok(v) => {
             let mut builder_p1 = Builder::default();
             let mut builder_p2 = Builder::default();
@@ -138,15 +496,16 @@ pub async fn handle_buscar_patentes(input: &String, is_plate: bool) {
 
             let table_p1 = builder_p1.build().with(Style::rounded());
             let table_p2 = builder_p2.build().with(Style::rounded());
-            println!("{}\n{}", table_p1.to_string(), table_p2.to_string());
+            println!("{}\n{}", table_p1, table_p2);
         }
         Err(e) => println!("Error: {}", e),
     };
 }
 
 pub async fn handle_buscar_leaks(query: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
+    let client = new_client();
     match client.search_leaks(query.to_string()).await {
+        Ok(r) if json_output() => print_json(&r),
         Ok(r) => {
             let mut builder = Builder::default();
             builder.set_columns(["Email", "Password"]);
@@ -156,19 +515,18 @@ pub async fn handle_buscar_leaks(query: &String) {
             }
 
             let table = builder.build().with(Style::rounded());
-            println!("{}", table.to_string());
+            println!("{}", table);
         }
         Err(e) => println!("Error: {}", e),
     };
 }
 
-pub async fn handle_buscar_dni_profesional(dni: &String, gender: &usize) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    match client
-        .search_profesional_dni(dni.to_string(), *gender)
-        .await
-    {
+pub async fn handle_buscar_dni_profesional(dni: &Dni, gender: Gender) {
+    let client = new_client();
+    match client.search_profesional_dni(dni.clone(), gender).await {
+        Ok(r) if json_output() => print_json(&r),
         Ok(r) => {
+            let edad = r.age();
             let mut builder_datos = Builder::default();
             let mut builder_localidad = Builder::default();
             let mut builder_coberturas = Builder::default();
@@ -205,11 +563,15 @@ pub async fn handle_buscar_dni_profesional(dni: &String, gender: &usize) {
                 r.nombres.unwrap_or(String::default()),
                 r.apellido.unwrap_or(String::default()),
                 r.pais.unwrap_or(String::default()),
-                r.fecha_nacimiento.unwrap_or(String::default()),
-                r.fallecido.unwrap_or(String::default()),
-                r.edad.unwrap_or(0).to_string(),
+                format_date(r.fecha_nacimiento),
+                match r.fallecido.as_ref().and_then(Parsed::get) {
+                    Some(true) => String::from("Si"),
+                    Some(false) => String::from("No"),
+                    None => r.fallecido.map(|f| f.to_string()).unwrap_or_default(),
+                },
+                edad.map(|e| e.to_string()).unwrap_or_default(),
                 r.cuil.unwrap_or(String::default()),
-                r.emision.unwrap_or(String::default()),
+                format_date(r.emision),
             ]);
 
             builder_localidad.add_record([
@@ -230,59 +592,61 @@ pub async fn handle_buscar_dni_profesional(dni: &String, gender: &usize) {
                     cob.cobertura.unwrap_or(String::default()),
                     cob.nombre.unwrap_or(String::default()),
                     cob.documento.unwrap_or(String::default()),
-                    cob.sexo.unwrap_or(String::default()),
+                    cob.sexo.map(|s| s.to_string()).unwrap_or_default(),
                 ]);
             }
 
             let table_datos = builder_datos.build().with(Style::rounded());
             let table_localidad = builder_localidad.build().with(Style::rounded());
             let table_coberturas = builder_coberturas.build().with(Style::rounded());
-            println!(
-                "{}\n{}\n{}",
-                table_datos.to_string(),
-                table_localidad.to_string(),
-                table_coberturas.to_string(),
-            );
+            println!("{}\n{}\n{}", table_datos, table_localidad, table_coberturas,);
         }
         Err(e) => println!("{}", e),
     };
 }
 
-pub async fn handle_buscar_nombre(nombre: &String, params: HashMap<&str, String>) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    match client.search_name(nombre.to_string(), Some(params)).await {
+pub async fn handle_buscar_nombre(nombre: &String, filters: NameSearchFilters) {
+    let client = new_client();
+    match client.search_name(nombre.to_string(), Some(filters)).await {
+        Ok(r) if json_output() => print_json(&r),
         Ok(r) => {
             let mut builder_results = Builder::default();
             builder_results.set_columns(["CUIT", "Nombre", "Provincia"]);
 
             for per in r {
-                builder_results.add_record([per.documento, per.nombre, per.provincia]);
+                builder_results.add_record([
+                    per.documento.unwrap_or(String::from("-")),
+                    per.nombre.unwrap_or(String::from("-")),
+                    per.provincia.unwrap_or(String::from("-")),
+                ]);
             }
 
             let table_results = builder_results.build().with(Style::rounded());
-            println!("{}", table_results.to_string());
+            println!("{}", table_results);
         }
         Err(e) => println!("{}", e),
     };
 }
 
-pub async fn handle_buscar_movistar(numero: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    match client.search_movistar_email(numero.to_string()).await {
+pub async fn handle_buscar_movistar(numero: &PhoneNumber) {
+    let client = new_client();
+    match client.search_movistar_email(numero.clone()).await {
+        Ok(r) if json_output() => print_json(&r),
         Ok(r) => {
             let mut builder_results = Builder::default();
             builder_results.set_columns(["Numero", "Email"]);
             builder_results.add_record([r.numero, r.email]);
             let table_results = builder_results.build().with(Style::rounded());
-            println!("{}", table_results.to_string());
+            println!("{}", table_results);
         }
         Err(e) => println!("{}", e),
     };
 }
 
 pub async fn handle_buscar_vecinos(direccion: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
+    let client = new_client();
     match client.search_by_address(direccion.to_string()).await {
+        Ok(r) if json_output() => print_json(&r),
         Ok(r) => {
             let mut builder_vecinos_datos = Builder::default();
             let mut builder_vecinos_direccion = Builder::default();
@@ -316,19 +680,16 @@ pub async fn handle_buscar_vecinos(direccion: &String) {
 
             let table_vecinos_datos = builder_vecinos_datos.build().with(Style::rounded());
             let table_vecinos_direccion = builder_vecinos_direccion.build().with(Style::rounded());
-            println!(
-                "{}\n{}",
-                table_vecinos_direccion.to_string(),
-                table_vecinos_datos.to_string(),
-            );
+            println!("{}\n{}", table_vecinos_direccion, table_vecinos_datos,);
         }
         Err(e) => println!("{}", e),
     };
 }
 
-pub async fn handle_buscar_celular(numero: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    match client.search_phone(numero.to_string()).await {
+pub async fn handle_buscar_celular(numero: &PhoneNumber) {
+    let client = new_client();
+    match client.search_phone(numero.clone()).await {
+        Ok(r) if json_output() => print_json(&r),
         Ok(r) => {
             let mut builder_celulares = Builder::default();
             builder_celulares.set_columns([
@@ -343,64 +704,77 @@ pub async fn handle_buscar_celular(numero: &String) {
             ]);
             for vc in r {
                 builder_celulares.add_record([
-                    vc.documento,
-                    vc.nombre,
-                    vc.provincia,
-                    vc.localidad,
-                    vc.direccion,
-                    vc.codigo_postal,
-                    vc.celular,
-                    vc.empresa,
+                    vc.documento.unwrap_or(String::from("-")),
+                    vc.nombre.unwrap_or(String::from("-")),
+                    vc.provincia.unwrap_or(String::from("-")),
+                    vc.localidad.unwrap_or(String::from("-")),
+                    vc.direccion.unwrap_or(String::from("-")),
+                    vc.codigo_postal.unwrap_or(String::from("-")),
+                    vc.celular.unwrap_or(String::from("-")),
+                    vc.empresa.unwrap_or(String::from("-")),
                 ]);
             }
 
             let table_celulares = builder_celulares.build().with(Style::rounded());
-            println!("{}", table_celulares.to_string());
+            println!("{}", table_celulares);
         }
         Err(e) => println!("{}", e),
     };
 }
 
-pub async fn handle_buscar_celular_magic(numero: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    match client.search_phone_magic(numero.to_string()).await {
+pub async fn handle_buscar_celular_magic(numero: &PhoneNumber) {
+    let client = new_client();
+    match client.search_phone_magic(numero.clone()).await {
+        Ok(vc) if json_output() => print_json(&vc),
         Ok(vc) => {
             let mut builder_results = Builder::default();
             builder_results.set_columns(["Nombre", "Apellido", "Email", "Numero"]);
             builder_results.add_record([vc.nombre, vc.apellido, vc.email, vc.numero]);
 
             let table_results = builder_results.build().with(Style::rounded());
-            println!("{}", table_results.to_string());
+            println!("{}", table_results);
         }
         Err(e) => println!("{}", e),
     };
 }
 
-pub async fn handle_buscar_cbu(cbu_alias: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
-    match client.search_cbu(cbu_alias.to_string()).await {
+pub async fn handle_buscar_cbu(cbu_alias: &CbuOrAlias) {
+    let client = new_client();
+    match client.search_cbu(cbu_alias.clone()).await {
+        Ok(vc) if json_output() => print_json(&vc),
         Ok(vc) => {
             let mut builder_results = Builder::default();
             builder_results.set_columns(["Nombre", "CUIT", "Banco", "CBU", "Tipo"]);
-            builder_results.add_record([vc.nombre, vc.cuit, vc.banco, vc.cbu, vc.cuenta_tipo]);
+            builder_results.add_record([
+                vc.nombre.unwrap_or(String::from("-")),
+                vc.cuit.unwrap_or(String::from("-")),
+                vc.banco.unwrap_or(String::from("-")),
+                vc.cbu.unwrap_or(String::from("-")),
+                vc.cuenta_tipo.unwrap_or(String::from("-")),
+            ]);
 
             let table_results = builder_results.build().with(Style::rounded());
-            println!("{}", table_results.to_string());
+            println!("{}", table_results);
         }
         Err(e) => println!("{}", e),
     };
 }
 
 pub async fn handle_buscar_email(email: &String) {
-    let mut client = SigmaClient::from_creds(read_credentials().unwrap());
+    let client = new_client();
     match client.search_email(email.to_string()).await {
+        Ok(vc) if json_output() => print_json(&vc),
         Ok(vc) => {
             let mut builder_results = Builder::default();
             builder_results.set_columns(["Nombre", "Apellido", "Email"]);
-            builder_results.add_record([vc.nombre, vc.apellido, vc.email]);
+            builder_results.add_record([
+                vc.nombre.unwrap_or(String::from("-")),
+                vc.apellido.unwrap_or(String::from("-")),
+                vc.email.unwrap_or(String::from("-")),
+            ]);
 
             let table_results = builder_results.build().with(Style::rounded());
-            println!("{}", table_results.to_string());
+            println!("{}", table_results);
         }
         Err(e) => println!("{}", e),
     };
@@ -1,7 +1,11 @@
+mod config;
+mod cuota;
 mod handlers;
+mod secret;
 mod subcommands;
 use clap::{Parser, Subcommand};
-use std::collections::HashMap;
+use config::OutputFormat;
+use rusigma::filters::NameSearchFilters;
 
 /// Sigma-CLI es una interfaz por consola que permite utilizar los endpoints de busqueda de datos Sigma a través de una terminal.
 #[derive(Parser, Debug)]
@@ -10,11 +14,27 @@ use std::collections::HashMap;
 struct Cli {
     #[clap(subcommand)]
     command: Commands,
+    /// Muestra avisos sobre respuestas que no coinciden con el formato esperado
+    #[clap(long, global = true)]
+    verbose: bool,
+    /// No usa ni guarda respuestas en el cache
+    #[clap(long, global = true)]
+    no_cache: bool,
+    /// Vuelve a consultar aunque la respuesta este en el cache y la guarda de nuevo
+    #[clap(long, global = true, conflicts_with = "no-cache")]
+    refresh: bool,
+    /// Perfil de la configuracion a usar, por defecto el indicado en default_profile
+    #[clap(long, global = true)]
+    profile: Option<String>,
+    /// Formato de salida, por defecto el del perfil
+    #[clap(long, global = true, value_enum)]
+    formato: Option<OutputFormat>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     Login(subcommands::Login),
+    Logout(subcommands::Logout),
     BuscarDNIStandard(subcommands::BuscarDNIStandard),
     BuscarCelularesDNI(subcommands::BuscarCelualaresDNI),
     BuscarPatente(subcommands::BuscarPatente),
@@ -28,11 +48,16 @@ enum Commands {
     BuscarCelularesMagic(subcommands::BuscarCelularesMagic),
     BuscarCBU(subcommands::BuscarCBU),
     BuscarEmail(subcommands::BuscarEmail),
+    Cuota(subcommands::Cuota),
+    Cache(subcommands::Cache),
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    handlers::set_verbose(cli.verbose);
+    handlers::set_cache_options(cli.no_cache, cli.refresh);
+    handlers::set_profile(cli.profile.clone(), cli.formato);
 
     match &cli.command {
         Commands::BuscarDNIStandard(dni) => {
@@ -42,10 +67,10 @@ async fn main() {
             handlers::handle_buscar_celulares_dni(&dni.dni).await;
         }
         Commands::BuscarPatente(patente) => {
-            handlers::handle_buscar_patentes(&patente.patente, true).await;
+            handlers::handle_buscar_patentes(&patente.patente).await;
         }
         Commands::BuscarPatenteDNI(dni) => {
-            handlers::handle_buscar_patentes(&dni.dni, false).await;
+            handlers::handle_buscar_patentes_dni(&dni.dni).await;
         }
 
         Commands::BuscarLeaks(query) => {
@@ -53,32 +78,28 @@ async fn main() {
         }
 
         Commands::BuscarDNIProfesional(input) => {
-            handlers::handle_buscar_dni_profesional(&input.dni, &input.genero).await;
+            handlers::handle_buscar_dni_profesional(&input.dni, input.genero).await;
         }
 
         Commands::BuscarNombre(input) => {
-            let mut params = HashMap::new();
-
-            if input.provincia.is_some() {
-                params.insert(
-                    "provincia_nombre",
-                    input.provincia.as_ref().unwrap().to_string(),
-                );
+            let mut filters = NameSearchFilters::builder();
+            if let Some(provincia) = input.provincia {
+                filters = filters.province(provincia);
             }
-
-            if input.localidad.is_some() {
-                params.insert("localidad", input.localidad.as_ref().unwrap().to_string());
+            if let Some(localidad) = &input.localidad {
+                filters = filters.locality(localidad);
             }
-
-            if input.edadmin.is_some() {
-                params.insert("edad_desde", input.edadmin.as_ref().unwrap().to_string());
+            if let Some(edadmin) = input.edadmin {
+                filters = filters.min_age(edadmin);
             }
-
-            if input.edadmax.is_some() {
-                params.insert("edad_hasta", input.edadmax.as_ref().unwrap().to_string());
+            if let Some(edadmax) = input.edadmax {
+                filters = filters.max_age(edadmax);
             }
 
-            handlers::handle_buscar_nombre(&input.nombre, params).await;
+            match filters.build() {
+                Ok(filters) => handlers::handle_buscar_nombre(&input.nombre, filters).await,
+                Err(e) => handlers::exit_with_error(&e.to_string()),
+            }
         }
 
         Commands::BuscarMovistar(num) => {
@@ -105,8 +126,20 @@ async fn main() {
             handlers::handle_buscar_email(&email.email).await;
         }
 
+        Commands::Cuota(_) => {
+            handlers::handle_cuota();
+        }
+
+        Commands::Cache(cache) => match cache.command {
+            subcommands::CacheCommand::Purge => handlers::handle_cache_purge(),
+        },
+
         Commands::Login(creds) => {
-            handlers::handle_login(&creds.usuario, &creds.password).await;
+            handlers::handle_login(&creds.usuario, &creds.password, creds.cifrar).await;
+        }
+
+        Commands::Logout(_) => {
+            handlers::handle_logout();
         }
     }
 }
@@ -1,4 +1,19 @@
-use clap::Args;
+use clap::{Args, Subcommand};
+use rusigma::cbu::CbuOrAlias;
+use rusigma::document::{Cuit, Dni, Gender};
+use rusigma::filters::Province;
+use rusigma::phone::PhoneNumber;
+use rusigma::plate::Plate;
+
+/// DNI escrito tal cual o tomado de un CUIT/CUIL de 11 digitos
+fn dni_or_cuit(s: &str) -> Result<Dni, rusigma::Error> {
+    let digits = s.chars().filter(char::is_ascii_digit).count();
+    if s.contains('-') || digits == 11 {
+        s.parse::<Cuit>()?.dni()
+    } else {
+        s.parse()
+    }
+}
 
 #[derive(Args, Debug)]
 #[clap(long_about = "Loguearse y almacenar la token para futuros usos")]
@@ -7,22 +22,29 @@ pub struct Login {
     pub usuario: String,
     // Pasword de Sigma
     pub password: String,
+    /// Cifra la token con una frase de paso, tomada de SIGMA_TOKEN_PASSPHRASE o pedida en la terminal
+    #[clap(long)]
+    pub cifrar: bool,
 }
 
+#[derive(Args, Debug)]
+#[clap(long_about = "Borra la token guardada en el perfil")]
+pub struct Logout {}
+
 #[derive(Args, Debug)]
 #[clap(long_about = "Busca datos de una persona a traves de su DNI")]
 pub struct BuscarDNIStandard {
-    // DNI perteneciente a la persona a buscar
-    #[clap(value_parser)]
-    pub dni: String,
+    // DNI perteneciente a la persona a buscar, o su CUIT/CUIL
+    #[clap(value_parser = dni_or_cuit)]
+    pub dni: Dni,
 }
 
 #[derive(Args, Debug)]
 #[clap(long_about = "Busca los celulares de una persona a traves de su DNI")]
 pub struct BuscarCelualaresDNI {
-    // DNI perteneciente a la persona a buscar
-    #[clap(value_parser)]
-    pub dni: String,
+    // DNI perteneciente a la persona a buscar, o su CUIT/CUIL
+    #[clap(value_parser = dni_or_cuit)]
+    pub dni: Dni,
 }
 
 #[derive(Args, Debug)]
@@ -30,15 +52,15 @@ pub struct BuscarCelualaresDNI {
 pub struct BuscarPatente {
     // Patente del vehiculo a buscar
     #[clap(value_parser)]
-    pub patente: String,
+    pub patente: Plate,
 }
 
 #[derive(Args, Debug)]
 #[clap(long_about = "Busca el historial de vehiculos de una persona a traves de su DNI")]
 pub struct BuscarPatenteDNI {
-    // DNI perteneciente a la persona a buscar
-    #[clap(value_parser)]
-    pub dni: String,
+    // DNI perteneciente a la persona a buscar, o su CUIT/CUIL
+    #[clap(value_parser = dni_or_cuit)]
+    pub dni: Dni,
 }
 
 #[derive(Args, Debug)]
@@ -52,13 +74,13 @@ pub struct BuscarLeaks {
 #[derive(Args, Debug)]
 #[clap(long_about = "Busca datos exclusivos de una persona a traves de su DNI")]
 pub struct BuscarDNIProfesional {
-    // DNI perteneciente a la persona a buscar
-    #[clap(value_parser)]
-    pub dni: String,
+    // DNI perteneciente a la persona a buscar, o su CUIT/CUIL
+    #[clap(value_parser = dni_or_cuit)]
+    pub dni: Dni,
 
-    // Genero de la persona (1: Hombre, 2: Mujer, 3: Otro)
+    // Genero de la persona (M: Masculino, F: Femenino, X: Otro)
     #[clap(value_parser)]
-    pub genero: usize,
+    pub genero: Gender,
 }
 
 #[derive(Args, Debug)]
@@ -67,18 +89,18 @@ pub struct BuscarNombre {
     // Nombre de la persona a buscar
     #[clap(value_parser)]
     pub nombre: String,
-    // Provincia de la persona (opcional)
+    // Provincia de la persona (opcional), por ejemplo "cordoba" o "caba"
     #[clap(short, long, value_parser)]
-    pub provincia: Option<String>,
+    pub provincia: Option<Province>,
     // Localidad de la persona (opcional)
     #[clap(short, long, value_parser)]
     pub localidad: Option<String>,
     // Edad minima de la persona (opcional)
     #[clap(long, value_parser)]
-    pub edadmin: Option<String>,
+    pub edadmin: Option<u8>,
     // Edad maxima de la persona (opcional)
     #[clap(long, value_parser)]
-    pub edadmax: Option<String>,
+    pub edadmax: Option<u8>,
 }
 
 #[derive(Args, Debug)]
@@ -86,7 +108,7 @@ pub struct BuscarNombre {
 pub struct BuscarMovistar {
     // Numero de celular a buscar
     #[clap(value_parser)]
-    pub numero: String,
+    pub numero: PhoneNumber,
 }
 
 #[derive(Args, Debug)]
@@ -102,7 +124,7 @@ pub struct BuscarVecinos {
 pub struct BuscarCelular {
     // Numero de celular a buscar
     #[clap(value_parser)]
-    pub numero: String,
+    pub numero: PhoneNumber,
 }
 
 #[derive(Args, Debug)]
@@ -110,15 +132,15 @@ pub struct BuscarCelular {
 pub struct BuscarCelularesMagic {
     // Numero de celular a buscar
     #[clap(value_parser)]
-    pub numero: String,
+    pub numero: PhoneNumber,
 }
 
 #[derive(Args, Debug)]
 #[clap(long_about = "Busca el titular de una cuenta CBU a traves del numero CBU o alias")]
 pub struct BuscarCBU {
-    // CBU o alias a buscar
+    // CBU, CVU o alias a buscar
     #[clap(value_parser)]
-    pub cbu: String,
+    pub cbu: CbuOrAlias,
 }
 
 #[derive(Args, Debug)]
@@ -128,3 +150,24 @@ pub struct BuscarEmail {
     #[clap(value_parser)]
     pub email: String,
 }
+
+#[derive(Args, Debug)]
+#[clap(
+    long_about = "Muestra las consultas hechas hoy por endpoint y los limites diarios configurados en ~/.sigma.limits"
+)]
+pub struct Cuota {}
+
+#[derive(Args, Debug)]
+#[clap(
+    long_about = "Administra el cache cifrado de respuestas en ~/.sigma.cache, activo cuando SIGMA_CACHE_PASSPHRASE esta definida"
+)]
+pub struct Cache {
+    #[clap(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// Borra todas las respuestas guardadas
+    Purge,
+}
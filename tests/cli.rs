@@ -0,0 +1,168 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Home con una configuracion cuyo perfil por defecto consulta a `server`
+fn home_with_profile(name: &str, server: &MockServer) -> PathBuf {
+    let home = std::env::temp_dir().join(format!("sigma-cli-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&home);
+    let config_dir = home.join(".config").join("sigma");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config = config_dir.join("config.toml");
+    fs::write(
+        &config,
+        format!(
+            "version = 1\n\n[profiles.default]\ntoken = \"test-token\"\nplan = \"standard\"\nbase_url = \"{}\"\n",
+            server.uri()
+        ),
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&config, fs::Permissions::from_mode(0o600)).unwrap();
+    }
+    home
+}
+
+fn run(home: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sigma-cli"))
+        .args(args)
+        .env("HOME", home)
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("SIGMA_CACHE_PASSPHRASE")
+        .output()
+        .unwrap()
+}
+
+// El binario bloquea este hilo, el servidor de prueba responde desde otro
+#[tokio::test(flavor = "multi_thread")]
+async fn json_output_prints_the_response() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/standard/osint/argentina/resolver/dni"))
+        .and(header("sigma-key", "test-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "doc": "30123456",
+            "apellido": "PEREZ",
+            "nombres": "JUAN CARLOS",
+            "calle": "AV SIEMPRE VIVA 742",
+            "seccion": "12",
+            "circuito": "120A",
+            "tipo_doc": "DNI",
+            "localidad": "CAPITAL FEDERAL",
+            "provincia": "CIUDAD AUTONOMA DE BUENOS AIRES",
+            "codigo_postal": "1405"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let home = home_with_profile("json", &server);
+
+    let output = run(
+        &home,
+        &["buscar-dni-standard", "30123456", "--formato", "json"],
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    let printed: Value = serde_json::from_str(&stdout).expect(&stdout);
+    assert_eq!(printed["doc"], "30123456");
+    assert_eq!(printed["apellido"], "PEREZ");
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn usage_is_counted_per_profile() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/standard/osint/argentina/resolver/dni"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "doc": "30123456",
+            "apellido": "PEREZ",
+            "nombres": "JUAN CARLOS",
+            "calle": "AV SIEMPRE VIVA 742",
+            "seccion": "12",
+            "circuito": "120A",
+            "tipo_doc": "DNI",
+            "localidad": "CAPITAL FEDERAL",
+            "provincia": "CIUDAD AUTONOMA DE BUENOS AIRES",
+            "codigo_postal": "1405"
+        })))
+        .mount(&server)
+        .await;
+    let home = home_with_profile("usage", &server);
+    let config_dir = home.join(".config").join("sigma");
+    let mut config = fs::read_to_string(config_dir.join("config.toml")).unwrap();
+    config.push_str("\n[profiles.otro]\ntoken = \"otra-token\"\nplan = \"standard\"\n");
+    fs::write(config_dir.join("config.toml"), config).unwrap();
+
+    let output = run(&home, &["buscar-dni-standard", "30123456"]);
+
+    assert!(output.status.success());
+    let usage = fs::read_to_string(config_dir.join("usage-default.json")).unwrap();
+    assert!(usage.contains("standard_dni"), "{}", usage);
+    assert!(!config_dir.join("usage-otro.json").exists());
+    let output = run(&home, &["cuota", "--profile", "otro"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Consultas del perfil otro"), "{}", stdout);
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dni_arguments_take_the_dni_of_a_cuil() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/standard/osint/argentina/resolver/dni"))
+        .and(body_json(json!({ "dni": "12345678" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "doc": "12345678",
+            "apellido": "PEREZ",
+            "nombres": "JUAN CARLOS",
+            "calle": "AV SIEMPRE VIVA 742",
+            "seccion": "12",
+            "circuito": "120A",
+            "tipo_doc": "DNI",
+            "localidad": "CAPITAL FEDERAL",
+            "provincia": "CIUDAD AUTONOMA DE BUENOS AIRES",
+            "codigo_postal": "1405"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let home = home_with_profile("cuil", &server);
+
+    let output = run(&home, &["buscar-dni-standard", "20-12345678-6"]);
+    assert!(output.status.success());
+    let output = run(&home, &["buscar-dni-standard", "30-50000000-3"]);
+    assert!(!output.status.success());
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_age_range_fails_without_querying() {
+    let server = MockServer::start().await;
+    let home = home_with_profile("edades", &server);
+
+    let output = run(
+        &home,
+        &[
+            "buscar-nombre",
+            "juan perez",
+            "--edadmin",
+            "50",
+            "--edadmax",
+            "30",
+        ],
+    );
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("Error: "), "{}", stderr);
+    assert!(server.received_requests().await.unwrap().is_empty());
+    let _ = fs::remove_dir_all(&home);
+}